@@ -57,7 +57,7 @@ pub enum Commands {
         #[arg(short = 'f', long)]
         filter: Option<String>,
 
-        /// Return hoarded commands in structured format
+        /// Return hoarded commands as a json array instead of a table
         #[arg(short = 'j', long)]
         json: bool,
 
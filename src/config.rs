@@ -1,8 +1,10 @@
+use crate::core::SuffixStrategy;
 use crate::gui::prompts::prompt_input;
 use anyhow::{anyhow, Error, Result};
 use log::info;
 use serde::{Deserialize, Serialize};
 use std::{
+    collections::HashMap,
     fs,
     path::{Path, PathBuf},
 };
@@ -34,6 +36,29 @@ pub struct HoardConfig {
     pub sync_server_url: Option<String>,
     pub api_token: Option<String>,
     pub gpt_api_key: Option<String>,
+    // Colors of specific tags by color name, e.g. `prod: red`
+    #[serde(default)]
+    pub tag_colors: HashMap<String, String>,
+    // Trove files merged on top of the trove at `trove_path` when loading
+    #[serde(default)]
+    pub additional_troves: Vec<PathBuf>,
+    // Namespaces only differing in case are different namespaces
+    #[serde(default = "HoardConfig::default_case_sensitive_namespaces")]
+    pub case_sensitive_namespaces: bool,
+    // Length and characters of random name suffixes resolving name collisions
+    #[serde(default)]
+    pub suffix_length: Option<usize>,
+    #[serde(default)]
+    pub suffix_charset: Option<String>,
+    // How name suffixes resolving name collisions are picked: `random`, `counter` or `date`
+    #[serde(default)]
+    pub suffix_strategy: SuffixStrategy,
+    // Remove exact duplicate commands when loading the trove file
+    #[serde(default)]
+    pub dedupe_on_load: bool,
+    // Short names of namespaces, e.g. `k: kubernetes`, see `Trove::resolve_namespace`
+    #[serde(default)]
+    pub namespace_aliases: HashMap<String, String>,
 }
 
 impl Default for HoardConfig {
@@ -54,6 +79,14 @@ impl Default for HoardConfig {
             sync_server_url: Some(Self::default_sync_server_url()),
             api_token: None,
             gpt_api_key: None,
+            tag_colors: HashMap::new(),
+            additional_troves: Vec::new(),
+            case_sensitive_namespaces: Self::default_case_sensitive_namespaces(),
+            suffix_length: None,
+            suffix_charset: None,
+            suffix_strategy: SuffixStrategy::default(),
+            dedupe_on_load: false,
+            namespace_aliases: HashMap::new(),
         }
     }
 }
@@ -76,6 +109,14 @@ impl HoardConfig {
             sync_server_url: Some(Self::default_sync_server_url()),
             api_token: None,
             gpt_api_key: None,
+            tag_colors: HashMap::new(),
+            additional_troves: Vec::new(),
+            case_sensitive_namespaces: Self::default_case_sensitive_namespaces(),
+            suffix_length: None,
+            suffix_charset: None,
+            suffix_strategy: SuffixStrategy::default(),
+            dedupe_on_load: false,
+            namespace_aliases: HashMap::new(),
         }
     }
 
@@ -101,14 +142,34 @@ impl HoardConfig {
             sync_server_url: self.sync_server_url,
             api_token: self.api_token,
             gpt_api_key: self.gpt_api_key,
+            tag_colors: self.tag_colors,
+            additional_troves: self.additional_troves,
+            case_sensitive_namespaces: self.case_sensitive_namespaces,
+            suffix_length: self.suffix_length,
+            suffix_charset: self.suffix_charset,
+            suffix_strategy: self.suffix_strategy,
+            dedupe_on_load: self.dedupe_on_load,
+            namespace_aliases: self.namespace_aliases,
         }
     }
 
-    fn default_parameter_token() -> String {
+    /// The configured parameter start and ending token, falling back to the default tokens if one is missing
+    pub fn parameter_tokens(&self) -> (String, String) {
+        (
+            self.parameter_token
+                .clone()
+                .unwrap_or_else(Self::default_parameter_token),
+            self.parameter_ending_token
+                .clone()
+                .unwrap_or_else(Self::default_ending_parameter_token),
+        )
+    }
+
+    pub fn default_parameter_token() -> String {
         "#".to_string()
     }
 
-    fn default_ending_parameter_token() -> String {
+    pub fn default_ending_parameter_token() -> String {
         "!".to_string()
     }
 
@@ -120,6 +181,10 @@ impl HoardConfig {
         true
     }
 
+    const fn default_case_sensitive_namespaces() -> bool {
+        true
+    }
+
     const fn default_colors(color_level: u8) -> (u8, u8, u8) {
         match color_level {
             0 => (242, 229, 188),
@@ -182,7 +247,8 @@ fn load_or_build(path: &Path) -> Result<HoardConfig, Error> {
             loaded_config.trove_path = Some(path_buf);
         }
         // Sanity check. If the config makes sense
-        assert!(loaded_config.parameter_token != loaded_config.parameter_ending_token, "Your parameter token {} is equal to your ending token {}. Please set one of them to another character!", loaded_config.parameter_token.as_ref().unwrap(), loaded_config.parameter_ending_token.as_ref().unwrap());
+        let (token, ending_token) = loaded_config.parameter_tokens();
+        assert!(token != ending_token, "Your parameter token {token} is equal to your ending token {ending_token}. Please set one of them to another character!");
 
         Ok(loaded_config)
     } else {
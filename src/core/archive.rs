@@ -0,0 +1,249 @@
+use chrono::{DateTime, Utc};
+use serde::{Deserialize, Serialize};
+use std::io::{Read, Write};
+
+use crate::core::error::TroveError;
+use crate::core::trove::Trove;
+
+const TROVE_ENTRY: &str = "trove.yaml";
+const MANIFEST_ENTRY: &str = "manifest.json";
+const BLOCK_SIZE: usize = 512;
+/// Largest entry size accepted when reading an archive, so a corrupt header can not exhaust memory
+const MAX_ENTRY_SIZE: usize = 64 * 1024 * 1024;
+
+/// Metadata stored next to the trove in an archive
+///
+/// - `version`: The hoard version the trove was stored with
+/// - `checksum`: CRC32 checksum (hex) of the archived `trove.yaml`
+/// - `exported_at`: The date and time the archive was created
+/// - `command_count`: The number of commands in the archived trove
+#[derive(Debug, Clone, PartialEq, Eq, Serialize, Deserialize)]
+pub struct ArchiveManifest {
+    pub version: String,
+    pub checksum: String,
+    pub exported_at: DateTime<Utc>,
+    pub command_count: usize,
+}
+
+fn checksum(data: &[u8]) -> String {
+    format!("{:08x}", crc32fast::hash(data))
+}
+
+/// Write a single file entry in (ustar) tar format
+fn write_tar_entry<W: Write>(w: &mut W, name: &str, data: &[u8]) -> Result<(), TroveError> {
+    let mut header = [0_u8; BLOCK_SIZE];
+    header[..name.len()].copy_from_slice(name.as_bytes());
+    header[100..107].copy_from_slice(b"0000644");
+    header[108..115].copy_from_slice(b"0000000");
+    header[116..123].copy_from_slice(b"0000000");
+    header[124..135].copy_from_slice(format!("{:011o}", data.len()).as_bytes());
+    header[136..147].copy_from_slice(format!("{:011o}", Utc::now().timestamp()).as_bytes());
+    header[156] = b'0';
+    header[257..263].copy_from_slice(b"ustar\0");
+    header[263..265].copy_from_slice(b"00");
+    // The checksum is calculated with the checksum field itself set to spaces
+    header[148..156].copy_from_slice(b"        ");
+    let sum: u32 = header.iter().map(|b| u32::from(*b)).sum();
+    header[148..156].copy_from_slice(format!("{sum:06o}\0 ").as_bytes());
+
+    w.write_all(&header)?;
+    w.write_all(data)?;
+    let padding = (BLOCK_SIZE - data.len() % BLOCK_SIZE) % BLOCK_SIZE;
+    w.write_all(&vec![0_u8; padding])?;
+    Ok(())
+}
+
+fn parse_octal(field: &[u8]) -> Result<usize, TroveError> {
+    let digits = String::from_utf8_lossy(field);
+    let digits = digits.trim_matches(|c: char| c == '\0' || c.is_whitespace());
+    usize::from_str_radix(digits, 8)
+        .map_err(|_| TroveError::Archive(format!("invalid octal number '{digits}' in header")))
+}
+
+/// Read all file entries of a (ustar) tar archive
+fn read_tar_entries<R: Read>(mut r: R) -> Result<Vec<(String, Vec<u8>)>, TroveError> {
+    let mut entries = Vec::new();
+    let mut header = [0_u8; BLOCK_SIZE];
+    loop {
+        if r.read_exact(&mut header).is_err() {
+            return Err(TroveError::Archive("unexpected end of archive".to_string()));
+        }
+        if header.iter().all(|b| *b == 0) {
+            return Ok(entries);
+        }
+        let expected_sum = parse_octal(&header[148..156])?;
+        let sum: usize = header
+            .iter()
+            .enumerate()
+            .map(|(i, b)| {
+                if (148..156).contains(&i) {
+                    32
+                } else {
+                    usize::from(*b)
+                }
+            })
+            .sum();
+        if sum != expected_sum {
+            return Err(TroveError::Archive("corrupt entry header".to_string()));
+        }
+        let name_end = header[..100].iter().position(|b| *b == 0).unwrap_or(100);
+        let name = String::from_utf8_lossy(&header[..name_end]).to_string();
+        let size = parse_octal(&header[124..136])?;
+        if size > MAX_ENTRY_SIZE {
+            return Err(TroveError::Archive(format!(
+                "entry '{name}' of {size} bytes is too large"
+            )));
+        }
+        let padded_size = size.div_ceil(BLOCK_SIZE) * BLOCK_SIZE;
+        // Grow the buffer with the data actually read instead of trusting the size of the header
+        let mut data = Vec::new();
+        r.by_ref()
+            .take(padded_size as u64)
+            .read_to_end(&mut data)
+            .map_err(|_| TroveError::Archive(format!("truncated entry '{name}'")))?;
+        if data.len() != padded_size {
+            return Err(TroveError::Archive(format!("truncated entry '{name}'")));
+        }
+        data.truncate(size);
+        entries.push((name, data));
+    }
+}
+
+impl Trove {
+    #[allow(dead_code)]
+    /// Write the trove collection as a tar archive containing `trove.yaml` and a `manifest.json`
+    ///
+    /// The manifest holds the hoard version, a checksum of `trove.yaml`, the export timestamp and the command count
+    pub fn to_archive<W: Write>(&self, mut w: W) -> Result<(), TroveError> {
+        let trove_yaml = serde_yaml::to_string(self)?;
+        let manifest = ArchiveManifest {
+            version: self.version.clone(),
+            checksum: checksum(trove_yaml.as_bytes()),
+            exported_at: Utc::now(),
+            command_count: self.commands.len(),
+        };
+        let manifest_json = serde_json::to_string_pretty(&manifest)?;
+
+        write_tar_entry(&mut w, MANIFEST_ENTRY, manifest_json.as_bytes())?;
+        write_tar_entry(&mut w, TROVE_ENTRY, trove_yaml.as_bytes())?;
+        // An archive ends with two empty blocks
+        w.write_all(&[0_u8; 2 * BLOCK_SIZE])?;
+        w.flush()?;
+        Ok(())
+    }
+
+    #[allow(dead_code)]
+    /// Read a trove collection from an archive created by `to_archive`
+    ///
+    /// The trove is normalized like a loaded trove file, see `Trove::try_load_trove_file`.
+    /// Returns `TroveError::ChecksumMismatch` if `trove.yaml` does not match the checksum of the manifest
+    pub fn from_archive<R: Read>(r: R) -> Result<(Self, ArchiveManifest), TroveError> {
+        let entries = read_tar_entries(r)?;
+        let entry = |name: &str| {
+            entries
+                .iter()
+                .find(|(entry_name, _)| entry_name == name)
+                .map(|(_, data)| data)
+                .ok_or_else(|| TroveError::Archive(format!("missing '{name}'")))
+        };
+        let manifest: ArchiveManifest = serde_json::from_slice(entry(MANIFEST_ENTRY)?)?;
+        let trove_yaml = entry(TROVE_ENTRY)?;
+
+        let actual = checksum(trove_yaml);
+        if actual != manifest.checksum {
+            return Err(TroveError::ChecksumMismatch {
+                expected: manifest.checksum,
+                actual,
+            });
+        }
+        let mut trove: Self = serde_yaml::from_slice(trove_yaml)?;
+        trove.normalize();
+        Ok((trove, manifest))
+    }
+}
+
+#[cfg(test)]
+mod test_commands {
+    use super::*;
+    use crate::core::HoardCmd;
+
+    fn trove() -> Trove {
+        let command = HoardCmd::default()
+            .with_name("test")
+            .with_namespace("test")
+            .with_command("echo test");
+        Trove::from_commands(&[command])
+    }
+
+    #[test]
+    fn archive_round_trip() {
+        let trove = trove();
+        let mut archive = Vec::new();
+        trove.to_archive(&mut archive).unwrap();
+        assert_eq!(0, archive.len() % BLOCK_SIZE);
+
+        let (loaded, manifest) = Trove::from_archive(archive.as_slice()).unwrap();
+        assert_eq!(1, manifest.command_count);
+        assert_eq!(trove.version, manifest.version);
+        assert_eq!(trove.commands, loaded.commands);
+    }
+
+    #[test]
+    fn archive_checksum_mismatch() {
+        let mut archive = Vec::new();
+        trove().to_archive(&mut archive).unwrap();
+        // Tamper with the archived trove content, which is stored in the block after its header
+        let content_start = archive
+            .windows(b"echo test".len())
+            .position(|w| w == b"echo test")
+            .unwrap();
+        archive[content_start] = b'E';
+
+        let result = Trove::from_archive(archive.as_slice());
+        assert!(matches!(result, Err(TroveError::ChecksumMismatch { .. })));
+    }
+
+    #[test]
+    fn archive_truncated() {
+        let mut archive = Vec::new();
+        trove().to_archive(&mut archive).unwrap();
+        archive.truncate(BLOCK_SIZE + 10);
+        assert!(matches!(
+            Trove::from_archive(archive.as_slice()),
+            Err(TroveError::Archive(_))
+        ));
+    }
+
+    #[test]
+    fn archive_entry_too_large() {
+        let mut archive = Vec::new();
+        trove().to_archive(&mut archive).unwrap();
+        // Claim the largest size the header can hold, fixing up the header checksum
+        archive[124..135].copy_from_slice(b"77777777777");
+        archive[148..156].copy_from_slice(b"        ");
+        let sum: u32 = archive[..BLOCK_SIZE].iter().map(|b| u32::from(*b)).sum();
+        archive[148..156].copy_from_slice(format!("{sum:06o}\0 ").as_bytes());
+
+        let result = Trove::from_archive(archive.as_slice());
+        assert!(
+            matches!(result, Err(TroveError::Archive(message)) if message.contains("too large"))
+        );
+    }
+
+    #[test]
+    fn archive_is_normalized() {
+        let command = HoardCmd::default()
+            .with_name("test")
+            .with_namespace("test")
+            .with_command("echo test");
+        let mut trove = Trove::from_commands(&[command]);
+        trove.commands[0].tags = vec!["a".to_string(), "a".to_string()];
+        trove.namespaces.clear();
+        let mut archive = Vec::new();
+        trove.to_archive(&mut archive).unwrap();
+
+        let (loaded, _) = Trove::from_archive(archive.as_slice()).unwrap();
+        assert_eq!(vec!["a"], loaded.commands[0].tags);
+        assert!(loaded.namespaces.contains("test"));
+    }
+}
@@ -0,0 +1,96 @@
+use console::style;
+use std::fmt::Write;
+
+use crate::config::HoardConfig;
+use crate::core::parameters::Parameterized;
+use crate::core::HoardCmd;
+
+/// Width of the label column of `HoardCmd::details`
+const LABEL_WIDTH: usize = 13;
+
+/// Append `label` and `value` as a row of the details view, aligning continuation lines of `value`
+fn push_row(details: &mut String, label: &str, value: &str) {
+    let mut lines = value.lines();
+    let label = format!("{label}:");
+    let _ = writeln!(
+        details,
+        "{}{}",
+        style(format!("{label:LABEL_WIDTH$}")).bold(),
+        lines.next().unwrap_or_default()
+    );
+    for line in lines {
+        let _ = writeln!(details, "{:LABEL_WIDTH$}{line}", "");
+    }
+}
+
+impl HoardCmd {
+    #[allow(dead_code)]
+    /// Render all details of the command as labeled rows, one field per row
+    ///
+    /// Unlike the table of `Trove::print_trove`, no field is truncated, multi-line commands are shown in full.
+    /// Parameters are listed with their defaults. Colors follow the settings of `console`, e.g. `NO_COLOR`
+    pub fn details(&self) -> String {
+        let mut details = String::new();
+        push_row(&mut details, "Name", &self.name);
+        push_row(&mut details, "Namespace", &self.namespace);
+        push_row(&mut details, "Command", &self.command);
+        if !self.description.is_empty() {
+            push_row(&mut details, "Description", &self.description);
+        }
+        if !self.tags.is_empty() {
+            push_row(&mut details, "Tags", &self.tags().join(", "));
+        }
+        let (token, ending_token) = self.parameter_tokens(&HoardConfig::default());
+        let mut parameters: Vec<String> = Vec::new();
+        for spec in self.parameter_specs(&token, &ending_token) {
+            let parameter = spec.default.map_or_else(
+                || spec.name.clone(),
+                |default| format!("{} (default: {default})", spec.name),
+            );
+            if !parameters.contains(&parameter) {
+                parameters.push(parameter);
+            }
+        }
+        if !parameters.is_empty() {
+            push_row(&mut details, "Parameters", &parameters.join("\n"));
+        }
+        if self.confirm {
+            push_row(&mut details, "Confirm", "required before running");
+        }
+        details
+    }
+
+    #[allow(dead_code)]
+    /// Print all details of the command, see `HoardCmd::details`
+    pub fn print_details(&self) {
+        print!("{}", self.details());
+    }
+}
+
+#[cfg(test)]
+mod test_commands {
+    use super::*;
+
+    #[test]
+    fn details_view() {
+        console::set_colors_enabled(false);
+        let command = HoardCmd::default()
+            .with_name("curl")
+            .with_namespace("http")
+            .with_command("curl #host!:#port=8080!\\\n  --verbose")
+            .with_tags_raw("web,debug");
+        let details = command.details();
+        assert_eq!(
+            "\
+Name:        curl
+Namespace:   http
+Command:     curl #host!:#port=8080!\\
+               --verbose
+Tags:        web, debug
+Parameters:  host
+             port (default: 8080)
+",
+            details
+        );
+    }
+}
@@ -0,0 +1,252 @@
+use std::collections::BTreeMap;
+use std::fmt;
+
+use crate::core::trove::Trove;
+use crate::core::HoardCmd;
+
+/// Line-level difference between two texts, in the order the lines appear
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum DiffLine<'a> {
+    /// A line of both texts
+    Same(&'a str),
+    /// A line only in the old text
+    Removed(&'a str),
+    /// A line only in the new text
+    Added(&'a str),
+}
+
+/// Compute the lines removed from `old` and added in `new`, based on their longest common subsequence of lines
+pub fn diff_lines<'a>(old: &'a str, new: &'a str) -> Vec<DiffLine<'a>> {
+    let old: Vec<&str> = old.lines().collect();
+    let new: Vec<&str> = new.lines().collect();
+    // lcs[i][j] is the length of the longest common subsequence of old[i..] and new[j..]
+    let mut lcs = vec![vec![0_usize; new.len() + 1]; old.len() + 1];
+    for i in (0..old.len()).rev() {
+        for j in (0..new.len()).rev() {
+            lcs[i][j] = if old[i] == new[j] {
+                lcs[i + 1][j + 1] + 1
+            } else {
+                lcs[i + 1][j].max(lcs[i][j + 1])
+            };
+        }
+    }
+    let mut lines = Vec::with_capacity(old.len().max(new.len()));
+    let (mut i, mut j) = (0, 0);
+    while i < old.len() && j < new.len() {
+        if old[i] == new[j] {
+            lines.push(DiffLine::Same(old[i]));
+            i += 1;
+            j += 1;
+        } else if lcs[i + 1][j] >= lcs[i][j + 1] {
+            lines.push(DiffLine::Removed(old[i]));
+            i += 1;
+        } else {
+            lines.push(DiffLine::Added(new[j]));
+            j += 1;
+        }
+    }
+    lines.extend(old[i..].iter().map(|line| DiffLine::Removed(line)));
+    lines.extend(new[j..].iter().map(|line| DiffLine::Added(line)));
+    lines
+}
+
+/// Render the line-level difference of `old` and `new` in unified diff style
+///
+/// Every line is prefixed with ` ` if it is in both texts, `-` if it was removed and `+` if it was added.
+/// Returns `None` if both texts are equal
+pub fn unified_diff(old: &str, new: &str) -> Option<String> {
+    if old == new {
+        return None;
+    }
+    let diff = diff_lines(old, new)
+        .into_iter()
+        .map(|line| match line {
+            DiffLine::Same(line) => format!(" {line}\n"),
+            DiffLine::Removed(line) => format!("-{line}\n"),
+            DiffLine::Added(line) => format!("+{line}\n"),
+        })
+        .collect();
+    Some(diff)
+}
+
+/// A field of a command that differs between two troves, see `TroveDiff`
+///
+/// - `field`: Name of the field, e.g. `command`
+/// - `old`: The value in the trove `Trove::diff` was called on
+/// - `new`: The value in the other trove
+#[allow(dead_code)]
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct FieldDiff {
+    pub field: &'static str,
+    pub old: String,
+    pub new: String,
+}
+
+/// A command with the same namespace and name in both troves, but different fields
+#[allow(dead_code)]
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct ChangedCommand {
+    pub namespace: String,
+    pub name: String,
+    pub fields: Vec<FieldDiff>,
+}
+
+/// Difference between two troves, see `Trove::diff`
+///
+/// Commands are matched by namespace and name and listed sorted by them
+/// - `only_in_self`: Commands only in the trove `Trove::diff` was called on, i.e. removed in the other trove
+/// - `only_in_other`: Commands only in the other trove, i.e. added in the other trove
+/// - `changed`: Commands in both troves whose command, description or tags differ
+#[allow(dead_code)]
+#[derive(Debug, Clone, Default, PartialEq)]
+pub struct TroveDiff {
+    pub only_in_self: Vec<HoardCmd>,
+    pub only_in_other: Vec<HoardCmd>,
+    pub changed: Vec<ChangedCommand>,
+}
+
+impl TroveDiff {
+    #[allow(dead_code)]
+    /// Check if both troves have the same commands
+    pub const fn is_empty(&self) -> bool {
+        self.only_in_self.is_empty() && self.only_in_other.is_empty() && self.changed.is_empty()
+    }
+}
+
+/// Lists added commands with `+`, removed commands with `-` and changed commands with `M`
+/// followed by their changed fields, and ends with a summary line like `git diff --stat`
+impl fmt::Display for TroveDiff {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        for c in &self.only_in_other {
+            writeln!(f, "+ {}/{}", c.namespace, c.name)?;
+        }
+        for c in &self.only_in_self {
+            writeln!(f, "- {}/{}", c.namespace, c.name)?;
+        }
+        for c in &self.changed {
+            writeln!(f, "M {}/{}", c.namespace, c.name)?;
+            for field in &c.fields {
+                writeln!(f, "    {}: '{}' -> '{}'", field.field, field.old, field.new)?;
+            }
+        }
+        write!(
+            f,
+            "{} added, {} removed, {} changed",
+            self.only_in_other.len(),
+            self.only_in_self.len(),
+            self.changed.len()
+        )
+    }
+}
+
+/// The fields of `old` and `new` that differ
+fn field_diffs(old: &HoardCmd, new: &HoardCmd) -> Vec<FieldDiff> {
+    [
+        ("command", old.command.clone(), new.command.clone()),
+        (
+            "description",
+            old.description.clone(),
+            new.description.clone(),
+        ),
+        ("tags", old.tags().join(","), new.tags().join(",")),
+    ]
+    .into_iter()
+    .filter(|(_, old, new)| old != new)
+    .map(|(field, old, new)| FieldDiff { field, old, new })
+    .collect()
+}
+
+impl Trove {
+    #[allow(dead_code)]
+    /// Compare the commands of the trove with the commands of `other`, matching commands by namespace and name
+    ///
+    /// The trove is taken as the old and `other` as the new state, e.g. to review a trove of another machine before merging it
+    pub fn diff(&self, other: &Self) -> TroveDiff {
+        let by_key = |trove: &Self| -> BTreeMap<(String, String), HoardCmd> {
+            trove
+                .commands
+                .iter()
+                .map(|c| ((c.namespace.clone(), c.name.clone()), c.clone()))
+                .collect()
+        };
+        let old = by_key(self);
+        let mut new = by_key(other);
+        let mut diff = TroveDiff::default();
+        for (key, old_command) in old {
+            match new.remove(&key) {
+                Some(new_command) => {
+                    let fields = field_diffs(&old_command, &new_command);
+                    if !fields.is_empty() {
+                        diff.changed.push(ChangedCommand {
+                            namespace: key.0,
+                            name: key.1,
+                            fields,
+                        });
+                    }
+                }
+                None => diff.only_in_self.push(old_command),
+            }
+        }
+        diff.only_in_other = new.into_values().collect();
+        diff
+    }
+}
+
+#[cfg(test)]
+mod test_commands {
+    use super::*;
+
+    fn command(name: &str, namespace: &str, command: &str) -> HoardCmd {
+        HoardCmd::default()
+            .with_name(name)
+            .with_namespace(namespace)
+            .with_command(command)
+    }
+
+    #[test]
+    fn trove_diff() {
+        let old = Trove::from_commands(&[
+            command("status", "git", "git status"),
+            command("st", "git", "git status -s"),
+            command("pods", "k8s", "kubectl get pods"),
+        ]);
+        let new = Trove::from_commands(&[
+            command("status", "git", "git status -sb").with_tags_raw("vcs"),
+            command("log", "git", "git log"),
+            command("pods", "k8s", "kubectl get pods"),
+        ]);
+        let diff = old.diff(&new);
+        assert!(!diff.is_empty());
+        assert_eq!("st", diff.only_in_self[0].name);
+        assert_eq!("log", diff.only_in_other[0].name);
+        assert_eq!(1, diff.changed.len());
+        let fields: Vec<&str> = diff.changed[0].fields.iter().map(|f| f.field).collect();
+        assert_eq!(vec!["command", "tags"], fields);
+        assert_eq!(
+            "+ git/log\n- git/st\nM git/status\n    command: 'git status' -> 'git status -sb'\n    tags: '' -> 'vcs'\n\
+            1 added, 1 removed, 1 changed",
+            diff.to_string()
+        );
+
+        assert!(old.diff(&old.clone()).is_empty());
+    }
+
+    #[test]
+    fn line_diff() {
+        assert_eq!(
+            vec![
+                DiffLine::Same("a"),
+                DiffLine::Removed("b"),
+                DiffLine::Added("B"),
+                DiffLine::Same("c"),
+                DiffLine::Added("d"),
+            ],
+            diff_lines("a\nb\nc", "a\nB\nc\nd")
+        );
+        assert_eq!(
+            Some("-echo a\n+echo b\n".to_string()),
+            unified_diff("echo a", "echo b")
+        );
+        assert_eq!(None, unified_diff("same", "same"));
+    }
+}
@@ -0,0 +1,247 @@
+use log::info;
+use std::collections::{BTreeMap, HashSet};
+
+use crate::config::HoardConfig;
+use crate::core::events::TroveEvent;
+use crate::core::trove::Trove;
+use crate::core::HoardCmd;
+
+/// Which commands `Trove::dedup` treats as duplicates of each other
+#[allow(dead_code)]
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum DuplicateKey {
+    /// Same namespace, name and command, e.g. left over from a bad merge
+    Exact,
+    /// Same namespace and command, regardless of the name
+    Command,
+    /// Same namespace and command after removing formatting differences, compared by `HoardCmd::content_id`
+    Normalized,
+}
+
+impl DuplicateKey {
+    /// The key of `command`, equal for all duplicates of it
+    fn of(self, command: &HoardCmd) -> String {
+        match self {
+            Self::Exact => format!(
+                "{}\0{}\0{}",
+                command.namespace, command.name, command.command
+            ),
+            Self::Command => format!("{}\0{}", command.namespace, command.command),
+            Self::Normalized => command.content_id(),
+        }
+    }
+}
+
+impl Trove {
+    #[allow(dead_code)]
+    /// Find command bodies that are stored in more than one namespace
+    ///
+    /// Returns each duplicated body, sorted, together with every `(namespace, name)` it is stored as.
+    /// If `normalize_whitespace` is set, bodies only differing in formatting are treated as identical
+    /// and the normalized body is returned, see `HoardCmd::normalized_command`
+    pub fn cross_namespace_duplicates(
+        &self,
+        normalize_whitespace: bool,
+    ) -> Vec<(String, Vec<(String, String)>)> {
+        let mut groups: BTreeMap<String, Vec<(String, String)>> = BTreeMap::new();
+        for command in &self.commands {
+            let body = if normalize_whitespace {
+                command.normalized_command()
+            } else {
+                command.command.clone()
+            };
+            groups
+                .entry(body)
+                .or_default()
+                .push((command.namespace.clone(), command.name.clone()));
+        }
+        groups
+            .into_iter()
+            .filter(|(_, locations)| {
+                locations
+                    .iter()
+                    .map(|(namespace, _)| namespace)
+                    .collect::<HashSet<_>>()
+                    .len()
+                    > 1
+            })
+            .collect()
+    }
+
+    #[allow(dead_code)]
+    /// Group all commands storing the exact same body, regardless of their name and namespace
+    ///
+    /// Only groups with more than one command are returned. Groups are sorted by their body,
+    /// the commands of a group keep their order in the trove
+    pub fn find_duplicate_commands(&self) -> Vec<Vec<&HoardCmd>> {
+        let mut groups: BTreeMap<&str, Vec<&HoardCmd>> = BTreeMap::new();
+        for command in &self.commands {
+            groups.entry(&command.command).or_default().push(command);
+        }
+        groups
+            .into_values()
+            .filter(|group| group.len() > 1)
+            .collect()
+    }
+
+    /// Remove exact duplicate commands if `dedupe_on_load` is set in `config`, logging how many were removed
+    ///
+    /// See `Trove::dedup` with `DuplicateKey::Exact`
+    pub fn dedupe_if_configured(&mut self, config: &HoardConfig) {
+        if config.dedupe_on_load {
+            let removed = self.dedup(DuplicateKey::Exact);
+            if removed > 0 {
+                info!("Removed {removed} duplicate command(s) from the trove");
+            }
+        }
+    }
+
+    /// Remove commands that are duplicates of an earlier command by `key`, keeping the first one
+    ///
+    /// The namespace set is rebuilt from the remaining commands. Returns the number of removed commands
+    pub fn dedup(&mut self, key: DuplicateKey) -> usize {
+        let mut seen: HashSet<String> = HashSet::new();
+        let keep: Vec<bool> = self
+            .commands
+            .iter()
+            .map(|c| seen.insert(key.of(c)))
+            .collect();
+        for (c, _) in self.commands.iter().zip(&keep).filter(|(_, keep)| !**keep) {
+            self.emit(|| TroveEvent::Removed {
+                namespace: c.namespace.clone(),
+                name: c.name.clone(),
+            });
+        }
+        let before = self.commands.len();
+        let mut keep = keep.into_iter();
+        self.commands.retain(|_| keep.next().unwrap_or(true));
+        self.rebuild_namespaces();
+        before - self.commands.len()
+    }
+}
+
+#[cfg(test)]
+mod test_commands {
+    use super::*;
+
+    #[test]
+    fn duplicates_across_namespaces() {
+        let commands = vec![
+            HoardCmd::default()
+                .with_name("status")
+                .with_namespace("git")
+                .with_command("git status"),
+            HoardCmd::default()
+                .with_name("st")
+                .with_namespace("work")
+                .with_command("git  status "),
+            HoardCmd::default()
+                .with_name("log")
+                .with_namespace("git")
+                .with_command("git log"),
+            HoardCmd::default()
+                .with_name("log2")
+                .with_namespace("git")
+                .with_command("git log"),
+        ];
+        let trove = Trove::from_commands(&commands);
+
+        // Duplicates within a single namespace are not reported
+        assert!(trove.cross_namespace_duplicates(false).is_empty());
+
+        let duplicates = trove.cross_namespace_duplicates(true);
+        assert_eq!(1, duplicates.len());
+        assert_eq!("git status", duplicates[0].0);
+        assert_eq!(
+            vec![
+                ("git".to_string(), "status".to_string()),
+                ("work".to_string(), "st".to_string())
+            ],
+            duplicates[0].1
+        );
+    }
+
+    #[test]
+    fn dedup_within_namespaces() {
+        let commands = vec![
+            HoardCmd::default()
+                .with_name("status")
+                .with_namespace("git")
+                .with_command("git status"),
+            HoardCmd::default()
+                .with_name("st")
+                .with_namespace("git")
+                .with_command("git  status"),
+            HoardCmd::default()
+                .with_name("status")
+                .with_namespace("work")
+                .with_command("git status"),
+            HoardCmd::default()
+                .with_name("grep")
+                .with_namespace("git")
+                .with_command("git grep 'a  b'"),
+            HoardCmd::default()
+                .with_name("grep2")
+                .with_namespace("git")
+                .with_command("git grep 'a b'"),
+        ];
+        let mut trove = Trove::from_commands(&commands);
+        assert_eq!(0, trove.dedup(DuplicateKey::Command));
+        assert_eq!(1, trove.dedup(DuplicateKey::Normalized));
+        let names: Vec<&str> = trove.commands.iter().map(|c| c.name.as_str()).collect();
+        assert_eq!(vec!["status", "status", "grep", "grep2"], names);
+    }
+
+    #[test]
+    fn find_duplicate_commands() {
+        let command = |name: &str, namespace: &str, command: &str| {
+            HoardCmd::default()
+                .with_name(name)
+                .with_namespace(namespace)
+                .with_command(command)
+        };
+        let trove = Trove::from_commands(&[
+            command("st", "work", "git status"),
+            command("log", "git", "git log"),
+            command("status", "git", "git status"),
+            command("lg", "git", "git log"),
+            command("unique", "git", "git fetch"),
+            command("spaced", "git", "git  log"),
+        ]);
+        let groups: Vec<Vec<&str>> = trove
+            .find_duplicate_commands()
+            .iter()
+            .map(|group| group.iter().map(|c| c.name.as_str()).collect())
+            .collect();
+        assert_eq!(vec![vec!["log", "lg"], vec!["st", "status"]], groups);
+    }
+
+    #[test]
+    fn dedupe_exact_duplicates() {
+        let command = |name: &str, namespace: &str, command: &str| {
+            HoardCmd::default()
+                .with_name(name)
+                .with_namespace(namespace)
+                .with_command(command)
+        };
+        let first = command("st", "git", "git status").with_description("first");
+        let mut trove = Trove::from_commands(&[
+            first,
+            command("st", "git", "git status"),
+            command("st", "git", "git status -sb"),
+            command("st", "work", "git status"),
+            command("status", "git", "git status"),
+        ]);
+        let mut config = HoardConfig::default();
+        trove.dedupe_if_configured(&config);
+        assert_eq!(5, trove.commands.len());
+
+        config.dedupe_on_load = true;
+        trove.dedupe_if_configured(&config);
+        assert_eq!(4, trove.commands.len());
+        assert_eq!("first", trove.commands[0].description);
+        assert_eq!(0, trove.dedup(DuplicateKey::Exact));
+        assert_eq!(1, trove.dedup(DuplicateKey::Command));
+        assert!(trove.has_namespace("work"));
+    }
+}
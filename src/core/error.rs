@@ -25,3 +25,124 @@ impl Error for HoardErr {
         &self.details
     }
 }
+
+/// A single violation found by `Trove::validate`
+///
+/// - `index`: Position of the offending command in `Trove::commands`
+/// - `reason`: Human-readable description of the violation
+#[derive(Debug, Clone, PartialEq, Eq, thiserror::Error)]
+#[error("Command {index}: {reason}")]
+pub struct TroveValidationError {
+    pub index: usize,
+    pub reason: String,
+}
+
+/// Errors returned by operations on a `Trove` collection
+#[allow(clippy::module_name_repetitions)]
+#[derive(Debug, thiserror::Error)]
+pub enum TroveError {
+    /// A query string could not be parsed. Points at the offending token
+    #[error("Invalid query token '{token}' at position {position}: {reason}")]
+    QueryParse {
+        token: String,
+        position: usize,
+        reason: String,
+    },
+
+    /// Reading or writing a file failed
+    #[error(transparent)]
+    Io(#[from] std::io::Error),
+
+    /// A trove could not be serialized or deserialized as yaml
+    #[error(transparent)]
+    Yaml(#[from] serde_yaml::Error),
+
+    /// A value could not be serialized or deserialized as json
+    #[error(transparent)]
+    Json(#[from] serde_json::Error),
+
+    /// A trove could not be written or read as csv
+    #[error(transparent)]
+    Csv(#[from] csv::Error),
+
+    /// A regular expression could not be compiled
+    #[error(transparent)]
+    Regex(#[from] regex::Error),
+
+    /// The version of a trove collection is no semantic version
+    #[error("Invalid trove version '{0}', expected a version like '1.2.3'")]
+    InvalidVersion(String),
+
+    /// A trove archive is malformed
+    #[error("Invalid trove archive: {0}")]
+    Archive(String),
+
+    /// The checksum of an archived trove does not match its manifest
+    #[error("Checksum mismatch: manifest expects {expected}, trove has {actual}")]
+    ChecksumMismatch { expected: String, actual: String },
+
+    /// No command matches the given (qualified) name
+    #[error("No matching command found with name: {0}")]
+    CommandNotFound(String),
+
+    /// A command to add has the same name and namespace as a command of the trove
+    #[error("Command '{namespace}/{name}' already exists as '{command}'")]
+    CommandCollision {
+        namespace: String,
+        name: String,
+        /// The command string of the existing command
+        command: String,
+    },
+
+    /// A command is missing required fields or has invalid parameter tokens
+    #[error("Cannot save invalid command: {0}")]
+    Invalid(String),
+
+    /// A command name given without namespace matches commands of several namespaces
+    #[error("Command name '{name}' is ambiguous, it exists in namespaces {}", namespaces.join(", "))]
+    AmbiguousCommand {
+        name: String,
+        namespaces: Vec<String>,
+    },
+
+    /// A command with the given qualified name already exists
+    #[error("A command named '{0}' already exists")]
+    CommandExists(String),
+
+    /// A parameter token of a command string is not followed by an ending token
+    #[error("Parameter at position {position} of '{command}' is missing its ending token '{ending_token}'")]
+    UnterminatedParameter {
+        command: String,
+        position: usize,
+        ending_token: String,
+    },
+
+    /// Parameters of a command have neither a value nor a default
+    #[error("Missing values for parameters of '{command}': {}", parameters.join(", "))]
+    MissingParameters {
+        command: String,
+        parameters: Vec<String>,
+    },
+
+    /// A command did not finish within its time limit and was killed
+    #[error("Command '{command}' did not finish within {timeout:?} and was killed")]
+    Timeout {
+        command: String,
+        timeout: std::time::Duration,
+    },
+
+    /// A command flagged with `confirm` was neither confirmed nor forced to run
+    #[error("Command '{0}' requires confirmation, confirm it or force it to run")]
+    ConfirmationRequired(String),
+
+    /// A single command entry of a trove file could not be deserialized and was skipped
+    #[error(
+        "Skipped invalid command entry {index}{}: {source}",
+        name.as_ref().map(|name| format!(" '{name}'")).unwrap_or_default()
+    )]
+    InvalidCommand {
+        index: usize,
+        name: Option<String>,
+        source: serde_yaml::Error,
+    },
+}
@@ -0,0 +1,252 @@
+use serde::{Deserialize, Serialize};
+use std::fmt;
+use std::sync::Arc;
+
+use crate::core::trove::Trove;
+use crate::core::HoardCmd;
+
+/// A change of a trove collection, reported to the event sink of the trove
+///
+/// Events are serializable, so a log of them can be persisted and replayed instead of merging trove files.
+/// Commands are identified by the namespace and name they had before the change
+#[allow(dead_code)]
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+#[serde(tag = "event", rename_all = "snake_case")]
+pub enum TroveEvent {
+    /// A new command was added
+    Added { command: HoardCmd },
+    /// The command `name` of `namespace` was removed
+    Removed { namespace: String, name: String },
+    /// The command `name` of `namespace` was replaced by `command`, which may have a new name or namespace
+    Updated {
+        namespace: String,
+        name: String,
+        command: HoardCmd,
+    },
+    /// All commands of namespace `old` were moved to namespace `new`
+    NamespaceRenamed { old: String, new: String },
+}
+
+type Sink = Arc<dyn Fn(&TroveEvent) + Send + Sync>;
+
+/// Receiver of the `TroveEvent`s of a trove collection, set with `Trove::with_event_sink`
+///
+/// Clones of a trove share the sink. Without a sink, no events are created
+#[derive(Clone, Default)]
+pub struct EventSink(Option<Sink>);
+
+impl fmt::Debug for EventSink {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        write!(
+            f,
+            "EventSink({})",
+            if self.0.is_some() { "set" } else { "none" }
+        )
+    }
+}
+
+impl Trove {
+    #[allow(dead_code)]
+    /// Report every change of the trove collection to `sink`
+    ///
+    /// Events are emitted by every method changing commands, so replaying them reproduces the trove:
+    /// - `add_command`, `add_commands` and `add_command_strict`
+    /// - `remove_command`, `remove_command_checked`, `remove_commands_by_tag`, `remove_command_by_id`,
+    ///   `remove_namespace_commands` and `remove_tombstoned`
+    /// - `update_command_by_id`, `update_command_meta`, `update_command_by_name`, `try_update_command_by_name`,
+    ///   `patch_command` and `record_usage`
+    /// - `move_command`, `rename_command` and `rename_command_by_name`
+    /// - `reorder_namespace`, `split_namespace`, `rename_namespace` and `normalize_separators`
+    /// - `replace_in_commands`, `lint_fix` and `dedup`
+    /// - `merge_trove`, the `merge_trove_*` methods and `apply_patch`
+    ///
+    /// Loading, migrating, sorting and restoring snapshots are not reported
+    #[must_use]
+    pub fn with_event_sink(self, sink: impl Fn(&TroveEvent) + Send + Sync + 'static) -> Self {
+        Self {
+            events: EventSink(Some(Arc::new(sink))),
+            ..self
+        }
+    }
+
+    /// Report the event created by `event` to the event sink, if there is one
+    pub fn emit(&self, event: impl FnOnce() -> TroveEvent) {
+        if let Some(sink) = &self.events.0 {
+            sink(&event());
+        }
+    }
+}
+
+#[cfg(test)]
+mod test_commands {
+    use super::*;
+    use crate::config::HoardConfig;
+    use std::sync::Mutex;
+
+    fn command(name: &str, namespace: &str) -> HoardCmd {
+        HoardCmd::default()
+            .with_name(name)
+            .with_namespace(namespace)
+            .with_command(&format!("echo {name}"))
+    }
+
+    #[test]
+    fn mutations_emit_events() {
+        let events = Arc::new(Mutex::new(Vec::new()));
+        let log = Arc::clone(&events);
+        let mut trove = Trove::default().with_event_sink(move |event: &TroveEvent| {
+            log.lock().unwrap().push(event.clone());
+        });
+
+        trove.add_command(command("a", "test"), true).unwrap();
+        trove
+            .add_command(command("a", "test").with_command("echo b"), true)
+            .unwrap();
+        trove.rename_command("test", "a", "b", false).unwrap();
+        trove.rename_namespace("test", "other").unwrap();
+        trove.remove_command("b").unwrap();
+        trove.add_command(command("c", "other"), true).unwrap();
+        let updated = trove.commands[0].clone().with_description("updated");
+        trove.update_command_by_id(&updated).unwrap();
+        trove.remove_namespace_commands("other").unwrap();
+
+        let events = events.lock().unwrap().clone();
+        let kinds: Vec<String> = events
+            .iter()
+            .map(|e| serde_json::to_value(e).unwrap()["event"].to_string())
+            .collect();
+        assert_eq!(
+            vec![
+                "\"added\"",
+                "\"updated\"",
+                "\"updated\"",
+                "\"updated\"",
+                "\"namespace_renamed\"",
+                "\"removed\"",
+                "\"added\"",
+                "\"updated\"",
+                "\"removed\""
+            ],
+            kinds
+        );
+        assert_eq!(
+            TroveEvent::Removed {
+                namespace: "other".to_string(),
+                name: "b".to_string()
+            },
+            events[5]
+        );
+        assert!(matches!(
+            &events[7],
+            TroveEvent::Updated { name, command, .. } if name == "c" && command.description == "updated"
+        ));
+        assert_eq!(
+            TroveEvent::Removed {
+                namespace: "other".to_string(),
+                name: "c".to_string()
+            },
+            events[8]
+        );
+        let json = serde_json::to_string(&events[2]).unwrap();
+        assert_eq!(events[2], serde_json::from_str(&json).unwrap());
+    }
+
+    #[test]
+    fn merged_tombstones_emit_events() {
+        let events = Arc::new(Mutex::new(Vec::new()));
+        let log = Arc::clone(&events);
+        let mut trove = Trove::from_commands(&[command("a", "test"), command("b", "test")])
+            .with_event_sink(move |event: &TroveEvent| {
+                log.lock().unwrap().push(event.clone());
+            });
+        let mut tombstone = command("a", "test");
+        tombstone.mut_set_deleted(true);
+        trove.apply_patch(&Trove::from_commands(&[tombstone]));
+        trove.remove_commands_by_tag("missing").unwrap_err();
+        let b = trove.commands[0].id.clone();
+        trove.remove_command_by_id(&b).unwrap();
+
+        let events = events.lock().unwrap().clone();
+        let removed: Vec<String> = events
+            .iter()
+            .map(|e| match e {
+                TroveEvent::Removed { name, .. } => name.clone(),
+                other => panic!("unexpected event {other:?}"),
+            })
+            .collect();
+        assert_eq!(vec!["a", "b"], removed);
+    }
+
+    fn replay(commands: &mut Vec<HoardCmd>, event: &TroveEvent) {
+        let find = |commands: &[HoardCmd], namespace: &str, name: &str| {
+            commands
+                .iter()
+                .position(|c| c.namespace == namespace && c.name == name)
+                .unwrap()
+        };
+        match event {
+            TroveEvent::Added { command } => commands.push(command.clone()),
+            TroveEvent::Removed { namespace, name } => {
+                let position = find(commands, namespace, name);
+                commands.remove(position);
+            }
+            TroveEvent::Updated {
+                namespace,
+                name,
+                command,
+            } => {
+                let position = find(commands, namespace, name);
+                commands[position] = command.clone();
+            }
+            TroveEvent::NamespaceRenamed { old, new } => {
+                for command in commands.iter_mut().filter(|c| c.namespace == *old) {
+                    command.namespace.clone_from(new);
+                }
+            }
+        }
+    }
+
+    #[test]
+    fn bulk_edits_can_be_replayed() {
+        let events = Arc::new(Mutex::new(Vec::new()));
+        let log = Arc::clone(&events);
+        let initial = vec![
+            command("a", "work"),
+            command("b", "work"),
+            command("a", "home"),
+            command("c", "k8s.pods"),
+        ];
+        let mut trove =
+            Trove::from_commands(&initial).with_event_sink(move |event: &TroveEvent| {
+                log.lock().unwrap().push(event.clone());
+            });
+
+        trove.split_namespace("work", "home", |c| c.name == "a");
+        trove.rename_namespace("work", "home").unwrap();
+        trove.record_usage("home/b").unwrap();
+        trove.reorder_namespace("home", &["b"]).unwrap();
+        trove.normalize_separators('/');
+        trove.replace_in_commands("echo", "printf", false).unwrap();
+        let tagged = command("d", "home").with_tags(vec![" x ".to_string(), "x".to_string()]);
+        trove.add_command(tagged, true).unwrap();
+        trove.lint_fix(&HoardConfig::default());
+
+        let mut replayed = initial;
+        for event in events.lock().unwrap().iter() {
+            replay(&mut replayed, event);
+        }
+        assert_eq!(trove.commands, replayed);
+        for (command, replayed) in trove.commands.iter().zip(&replayed) {
+            assert_eq!(command.usage_count, replayed.usage_count);
+            assert_eq!(command.order, replayed.order);
+        }
+        assert_eq!(vec!["x"], trove.commands[4].tags);
+    }
+
+    #[test]
+    fn no_events_without_sink() {
+        let mut trove = Trove::default();
+        trove.add_command(command("a", "test"), true).unwrap();
+        trove.emit(|| panic!("events must not be created without a sink"));
+    }
+}
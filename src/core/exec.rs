@@ -0,0 +1,182 @@
+use log::info;
+#[cfg(unix)]
+use std::os::unix::process::CommandExt;
+use std::process::{Child, Command, ExitStatus};
+use std::thread;
+use std::time::{Duration, Instant};
+
+use crate::core::error::TroveError;
+use crate::core::HoardCmd;
+use crate::gui::prompts::{prompt_yes_or_no, Confirmation};
+
+/// Interval in which a running child process is checked for completion
+const POLL_INTERVAL: Duration = Duration::from_millis(10);
+
+/// How commands flagged with `confirm` are allowed to run
+#[allow(dead_code)]
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum ExecMode {
+    /// Ask the user before running a flagged command
+    Interactive,
+    /// Run flagged commands only if `force` is set, for scripting
+    Scripted { force: bool },
+}
+
+impl HoardCmd {
+    /// Check if the command may run in `mode`
+    ///
+    /// Returns `TroveError::ConfirmationRequired` if the command is flagged with `confirm`
+    /// and was neither confirmed interactively nor forced
+    pub fn check_confirmation(&self, mode: ExecMode) -> Result<(), TroveError> {
+        let confirmed = !self.confirm
+            || match mode {
+                ExecMode::Interactive => matches!(
+                    prompt_yes_or_no(&format!("'{}' requires confirmation. Run it?", self.name)),
+                    Confirmation::Yes
+                ),
+                ExecMode::Scripted { force } => force,
+            };
+        if confirmed {
+            Ok(())
+        } else {
+            Err(TroveError::ConfirmationRequired(self.name.clone()))
+        }
+    }
+
+    /// Spawn `resolved` in a `sh` shell, inheriting stdin, stdout and stderr
+    ///
+    /// The shell runs in its own process group, so `kill_process_group` also reaches the processes it started
+    fn spawn(&self, resolved: &str, mode: ExecMode) -> Result<Child, TroveError> {
+        self.check_confirmation(mode)?;
+        info!("Running command {}", self.name);
+        let mut command = Command::new("sh");
+        command.arg("-c").arg(resolved);
+        #[cfg(unix)]
+        command.process_group(0);
+        Ok(command.spawn()?)
+    }
+
+    #[allow(dead_code)]
+    /// Run `resolved`, the command string with all parameters filled out, and wait for it to finish
+    ///
+    /// Commands flagged with `confirm` need to be confirmed as set by `mode`, see `HoardCmd::check_confirmation`.
+    /// Returns the exit status of the command
+    pub fn run(&self, resolved: &str, mode: ExecMode) -> Result<ExitStatus, TroveError> {
+        Ok(self.spawn(resolved, mode)?.wait()?)
+    }
+
+    #[allow(dead_code)]
+    /// Run `resolved` like `HoardCmd::run`, but kill it if it does not finish within `timeout`
+    ///
+    /// Returns `TroveError::Timeout` if the command had to be killed
+    pub fn run_with_timeout(
+        &self,
+        resolved: &str,
+        timeout: Duration,
+        mode: ExecMode,
+    ) -> Result<ExitStatus, TroveError> {
+        let mut child = self.spawn(resolved, mode)?;
+        let start = Instant::now();
+        loop {
+            if let Some(status) = child.try_wait()? {
+                return Ok(status);
+            }
+            if start.elapsed() >= timeout {
+                kill_process_group(&mut child)?;
+                child.wait()?;
+                return Err(TroveError::Timeout {
+                    command: self.name.clone(),
+                    timeout,
+                });
+            }
+            thread::sleep(POLL_INTERVAL);
+        }
+    }
+}
+
+/// Kill `child` together with all processes of its process group, e.g. the commands of a pipeline
+fn kill_process_group(child: &mut Child) -> Result<(), TroveError> {
+    #[cfg(unix)]
+    {
+        let killed = Command::new("kill")
+            .args(["-KILL", "--", &format!("-{}", child.id())])
+            .status()
+            .is_ok_and(|status| status.success());
+        if killed {
+            return Ok(());
+        }
+    }
+    Ok(child.kill()?)
+}
+
+#[cfg(test)]
+mod test_commands {
+    use super::*;
+
+    const SCRIPTED: ExecMode = ExecMode::Scripted { force: false };
+
+    #[test]
+    fn run_returns_exit_status() {
+        let command = HoardCmd::default().with_name("exit");
+        assert!(command.run("true", SCRIPTED).unwrap().success());
+        assert_eq!(Some(3), command.run("exit 3", SCRIPTED).unwrap().code());
+    }
+
+    /// Check if the process `pid` is gone or a zombie waiting to be reaped
+    #[cfg(unix)]
+    fn is_dead(pid: &str) -> bool {
+        std::fs::read_to_string(format!("/proc/{pid}/stat")).map_or(true, |stat| {
+            stat.rsplit(')').next().unwrap().trim().starts_with('Z')
+        })
+    }
+
+    #[test]
+    fn run_with_timeout_kills_command() {
+        let command = HoardCmd::default().with_name("sleep");
+        let result = command.run_with_timeout("sleep 5", Duration::from_millis(100), SCRIPTED);
+        assert!(matches!(
+            result,
+            Err(TroveError::Timeout { command, timeout })
+                if command == "sleep" && timeout == Duration::from_millis(100)
+        ));
+
+        let status = command
+            .run_with_timeout("exit 0", Duration::from_secs(5), SCRIPTED)
+            .unwrap();
+        assert!(status.success());
+    }
+
+    #[test]
+    fn confirm_requires_force() {
+        let mut command = HoardCmd::default().with_name("wipe");
+        command.confirm = true;
+        assert!(matches!(
+            command.run("true", SCRIPTED),
+            Err(TroveError::ConfirmationRequired(_))
+        ));
+        let status = command.run("true", ExecMode::Scripted { force: true });
+        assert!(status.unwrap().success());
+    }
+
+    #[cfg(unix)]
+    #[test]
+    fn run_with_timeout_kills_started_processes() {
+        let tmp_dir = tempfile::tempdir().unwrap();
+        let pid_file = tmp_dir.path().join("pid");
+        let command = HoardCmd::default().with_name("pipeline");
+        let resolved = format!("sleep 100 & echo $! > {}; wait", pid_file.display());
+        let result = command.run_with_timeout(&resolved, Duration::from_millis(200), SCRIPTED);
+        assert!(matches!(result, Err(TroveError::Timeout { .. })));
+
+        let pid = std::fs::read_to_string(pid_file).unwrap();
+        let pid = pid.trim();
+        // The killed process may not be reaped yet
+        for _ in 0..500 {
+            if is_dead(pid) {
+                break;
+            }
+            thread::sleep(POLL_INTERVAL);
+        }
+        assert!(is_dead(pid), "process {pid} is still running");
+    }
+}
@@ -0,0 +1,164 @@
+use crate::core::HoardCmd;
+
+/// Built-in explanations of common tools and flags
+///
+/// Keys are either a tool (`grep`), a flag of a specific tool (`rm -rf`) or a generic token (`|`).
+/// Flags of a specific tool take precedence over generic tokens
+pub const EXPLANATIONS: &[(&str, &str)] = &[
+    ("|", "pipe output into the next command"),
+    ("&&", "run the next command if this one succeeds"),
+    ("||", "run the next command if this one fails"),
+    (";", "run the next command afterwards"),
+    (">", "redirect output into a file, overwriting it"),
+    (">>", "redirect output into a file, appending to it"),
+    ("2>&1", "redirect errors to the output"),
+    ("&", "run in the background"),
+    ("sudo", "run as superuser"),
+    ("ls", "list directory contents"),
+    ("ls -l", "long listing format"),
+    ("ls -a", "include hidden entries"),
+    ("ls -la", "long listing format, include hidden entries"),
+    ("rm", "remove files or directories"),
+    ("rm -r", "recursive"),
+    ("rm -f", "force, ignore missing files"),
+    ("rm -rf", "recursive, force"),
+    ("cp", "copy files or directories"),
+    ("cp -r", "recursive"),
+    ("mv", "move or rename files"),
+    ("grep", "search for lines matching a pattern"),
+    ("grep -i", "ignore case"),
+    ("grep -r", "search directories recursively"),
+    ("grep -v", "invert match, select non-matching lines"),
+    ("tar", "create or extract archives"),
+    ("tar -xzf", "extract, gzip, from file"),
+    ("tar -czf", "create, gzip, to file"),
+    ("find", "search for files in a directory hierarchy"),
+    ("chmod", "change file permissions"),
+    ("chmod -R", "recursive"),
+    ("curl", "transfer data from or to a server"),
+    ("curl -L", "follow redirects"),
+    ("curl -s", "silent, no progress output"),
+    ("git", "version control"),
+    ("docker", "manage containers"),
+    ("kubectl", "control kubernetes clusters"),
+    ("ssh", "remote login"),
+];
+
+/// Tokens that separate commands of a pipeline or list
+const SEPARATORS: &[&str] = &["|", "&&", "||", ";", "&"];
+
+/// Split `command` at whitespace, keeping quoted sections together
+fn tokenize(command: &str) -> Vec<String> {
+    let mut tokens = Vec::new();
+    let mut current = String::new();
+    let mut quote: Option<char> = None;
+    for c in command.chars() {
+        match (quote, c) {
+            (Some(q), c) if c == q => {
+                quote = None;
+                current.push(c);
+            }
+            (None, '\'' | '"') => {
+                quote = Some(c);
+                current.push(c);
+            }
+            (None, c) if c.is_whitespace() => {
+                if !current.is_empty() {
+                    tokens.push(std::mem::take(&mut current));
+                }
+            }
+            _ => current.push(c),
+        }
+    }
+    if !current.is_empty() {
+        tokens.push(current);
+    }
+    tokens
+}
+
+impl HoardCmd {
+    #[allow(dead_code)]
+    /// Split the command string into tokens, each with an explanation from `EXPLANATIONS` if one is known
+    ///
+    /// Unknown tokens get `None`
+    pub fn explain(&self) -> Vec<(String, Option<String>)> {
+        self.explain_with(&[])
+    }
+
+    #[allow(dead_code)]
+    /// Like `HoardCmd::explain`, with `extra` explanations taking precedence over the built-in ones
+    ///
+    /// See `EXPLANATIONS` for the format of the entries
+    pub fn explain_with(&self, extra: &[(&str, &str)]) -> Vec<(String, Option<String>)> {
+        let lookup = |key: &str| {
+            extra
+                .iter()
+                .chain(EXPLANATIONS)
+                .find(|(k, _)| *k == key)
+                .map(|(_, explanation)| (*explanation).to_string())
+        };
+        let mut tool: Option<String> = None;
+        tokenize(&self.command)
+            .into_iter()
+            .map(|token| {
+                let explanation = if SEPARATORS.contains(&token.as_str()) {
+                    tool = None;
+                    lookup(&token)
+                } else if let Some(tool) = &tool {
+                    lookup(&format!("{tool} {token}")).or_else(|| lookup(&token))
+                } else {
+                    // `sudo` does not start a command of its own
+                    if token != "sudo" {
+                        tool = Some(token.clone());
+                    }
+                    lookup(&token)
+                };
+                (token, explanation)
+            })
+            .collect()
+    }
+}
+
+#[cfg(test)]
+mod test_commands {
+    use super::*;
+
+    #[test]
+    fn explain_tokens() {
+        let command =
+            HoardCmd::default().with_command("sudo rm -rf \"my dir\" && ls -l | grep -i foo");
+        let explained = command.explain();
+        let tokens: Vec<&str> = explained.iter().map(|(t, _)| t.as_str()).collect();
+        assert_eq!(
+            vec![
+                "sudo",
+                "rm",
+                "-rf",
+                "\"my dir\"",
+                "&&",
+                "ls",
+                "-l",
+                "|",
+                "grep",
+                "-i",
+                "foo"
+            ],
+            tokens
+        );
+        assert_eq!(Some("recursive, force".to_string()), explained[2].1);
+        assert_eq!(None, explained[3].1);
+        assert_eq!(Some("long listing format".to_string()), explained[6].1);
+        assert_eq!(Some("ignore case".to_string()), explained[9].1);
+        assert_eq!(None, explained[10].1);
+    }
+
+    #[test]
+    fn explain_with_extra_entries() {
+        let command = HoardCmd::default().with_command("hoard list -s");
+        let explained =
+            command.explain_with(&[("hoard", "command organizer"), ("hoard -s", "simple")]);
+        assert_eq!(Some("command organizer".to_string()), explained[0].1);
+        assert_eq!(None, explained[1].1);
+        assert_eq!(Some("simple".to_string()), explained[2].1);
+    }
+}
@@ -0,0 +1,638 @@
+use log::error;
+use regex::Regex;
+use serde::Serialize;
+use std::collections::HashMap;
+use std::fmt::Write;
+
+use crate::config::HoardConfig;
+use crate::core::error::TroveError;
+use crate::core::parameters::{CompletionSource, Parameterized};
+use crate::core::trove::Trove;
+use crate::core::HoardCmd;
+
+/// Columns of the csv written by `Trove::to_csv`
+const CSV_COLUMNS: [&str; 5] = ["name", "namespace", "command", "description", "tags"];
+
+/// Separator of the tags of a command within the `tags` column of a csv
+const CSV_TAG_SEPARATOR: &str = "|";
+
+/// A command as listed by `Trove::to_list_json`
+#[derive(Serialize)]
+struct ListEntry<'a> {
+    name: &'a str,
+    namespace: &'a str,
+    command: &'a str,
+    description: &'a str,
+    tags: Vec<&'a str>,
+}
+
+impl Trove {
+    /// List all commands as a json array of objects with `name`, `namespace`, `command`, `description` and `tags`
+    ///
+    /// Machine readable alternative to `print_trove`. Tags are listed as an array.
+    /// If the list can not be serialized, the error is logged and an empty string is returned
+    pub fn to_list_json(&self) -> String {
+        self.try_to_list_json().unwrap_or_else(|e| {
+            error!("Unable to serialize the command list: {e}");
+            String::new()
+        })
+    }
+
+    /// List all commands as json like `to_list_json`, returning an error if they can not be serialized
+    pub fn try_to_list_json(&self) -> Result<String, TroveError> {
+        let entries: Vec<ListEntry> = self
+            .commands
+            .iter()
+            .map(|c| ListEntry {
+                name: &c.name,
+                namespace: &c.namespace,
+                command: &c.command,
+                description: &c.description,
+                tags: c.tags(),
+            })
+            .collect();
+        Ok(serde_json::to_string_pretty(&entries)?)
+    }
+
+    #[allow(dead_code)]
+    /// Export all commands as csv, e.g. to review them in a spreadsheet
+    ///
+    /// Writes a header row and one row per command with the columns `name`, `namespace`, `command`, `description` and `tags`.
+    /// Tags are joined with `|`. Fields containing commas, quotes or newlines are quoted
+    pub fn to_csv(&self) -> Result<String, TroveError> {
+        let mut csv = Vec::new();
+        let mut writer = csv::Writer::from_writer(&mut csv);
+        writer.write_record(CSV_COLUMNS)?;
+        for c in &self.commands {
+            writer.write_record([
+                c.name.as_str(),
+                c.namespace.as_str(),
+                c.command.as_str(),
+                c.description.as_str(),
+                c.tags().join(CSV_TAG_SEPARATOR).as_str(),
+            ])?;
+        }
+        writer.flush()?;
+        drop(writer);
+        Ok(String::from_utf8_lossy(&csv).into_owned())
+    }
+
+    #[allow(dead_code)]
+    /// Import commands from csv in the format written by `to_csv`
+    ///
+    /// The columns are matched by the header row and may be in any order.
+    /// `description` and `tags` are optional, unknown columns are ignored.
+    /// Returns `TroveError::Invalid` if a required column is missing
+    pub fn from_csv(csv: &str) -> Result<Self, TroveError> {
+        let mut reader = csv::Reader::from_reader(csv.as_bytes());
+        let headers = reader.headers()?.clone();
+        let column = |name: &str| headers.iter().position(|h| h.trim() == name);
+        let mut required = Vec::new();
+        for name in &CSV_COLUMNS[..3] {
+            required.push(column(name).ok_or_else(|| {
+                TroveError::Invalid(format!("csv is missing the '{name}' column"))
+            })?);
+        }
+        let (description, tags) = (column(CSV_COLUMNS[3]), column(CSV_COLUMNS[4]));
+        let mut commands = Vec::new();
+        for record in reader.records() {
+            let record = record?;
+            let field = |i: Option<usize>| i.and_then(|i| record.get(i)).unwrap_or_default();
+            let tags = field(tags)
+                .split(CSV_TAG_SEPARATOR)
+                .map(str::trim)
+                .filter(|t| !t.is_empty())
+                .map(ToString::to_string)
+                .collect();
+            commands.push(
+                HoardCmd::default()
+                    .with_name(field(Some(required[0])))
+                    .with_namespace(field(Some(required[1])))
+                    .with_command(field(Some(required[2])))
+                    .with_description(field(description))
+                    .with_tags(tags),
+            );
+        }
+        Ok(Self::from_commands(&commands))
+    }
+
+    #[allow(dead_code)]
+    /// Export all commands as a markdown cheat sheet, e.g. for a wiki
+    ///
+    /// Commands are grouped by namespace under `##` headers and listed under a `###` header with their name.
+    /// The description comes first, followed by the tags as inline code and the command as a fenced code block.
+    /// Namespaces and the commands within them are sorted by name, so the output is stable
+    pub fn to_markdown(&self) -> String {
+        let mut commands: Vec<&HoardCmd> = self.commands.iter().collect();
+        commands.sort_by(|a, b| (&a.namespace, &a.name).cmp(&(&b.namespace, &b.name)));
+        let mut markdown = String::new();
+        let mut namespace: Option<&str> = None;
+        for c in commands {
+            if namespace != Some(c.namespace.as_str()) {
+                let _ = writeln!(markdown, "## {}\n", c.namespace);
+                namespace = Some(&c.namespace);
+            }
+            let _ = writeln!(markdown, "### {}\n", c.name);
+            if !c.description.is_empty() {
+                let _ = writeln!(markdown, "{}\n", c.description);
+            }
+            let tags = c.tags();
+            if !tags.is_empty() {
+                let badges: Vec<String> = tags.iter().map(|t| format!("`{t}`")).collect();
+                let _ = writeln!(markdown, "{}\n", badges.join(" "));
+            }
+            let fence = markdown_fence(&c.command);
+            let _ = writeln!(markdown, "{fence}sh\n{}\n{fence}\n", c.command.trim_end());
+        }
+        markdown
+    }
+
+    #[allow(dead_code)]
+    /// Export all commands of `namespace` that define environment variables as a `.env` style file
+    ///
+    /// Commands in the form of `export KEY=VALUE` or `KEY=VALUE` become a `KEY=VALUE` line.
+    /// Any other command of the namespace is skipped and noted with a comment.
+    pub fn to_env_file(&self, namespace: &str) -> String {
+        let re = Regex::new(r"^(?:export\s+)?([A-Za-z_][A-Za-z0-9_]*)=(.*)$").unwrap();
+        let namespace = self.resolve_namespace_alias(namespace);
+        let mut env_file = String::new();
+        for command in self.commands.iter().filter(|c| c.namespace == namespace) {
+            match re.captures(command.command.trim()) {
+                Some(captures) if !command.command.trim().contains('\n') => {
+                    let _ = writeln!(env_file, "{}={}", &captures[1], &captures[2]);
+                }
+                _ => {
+                    let _ = writeln!(
+                        env_file,
+                        "# Skipped '{}': not an environment variable definition",
+                        command.name
+                    );
+                }
+            }
+        }
+        env_file
+    }
+
+    #[allow(dead_code)]
+    /// Get a copy of the trove collection without the commands of `namespaces`
+    ///
+    /// Namespace aliases are resolved. The namespace set is rebuilt from the remaining commands
+    /// and aliases pointing to an excluded namespace are dropped. The trove itself is not changed
+    pub fn export_excluding(&self, namespaces: &[&str]) -> Self {
+        let excluded: Vec<&str> = namespaces
+            .iter()
+            .map(|n| self.resolve_namespace_alias(n))
+            .collect();
+        let commands: Vec<HoardCmd> = self
+            .commands
+            .iter()
+            .filter(|c| !excluded.contains(&c.namespace.as_str()))
+            .cloned()
+            .collect();
+        let mut trove = Self::from_commands(&commands);
+        trove.version.clone_from(&self.version);
+        trove.namespace_aliases = self
+            .namespace_aliases
+            .iter()
+            .filter(|(_, target)| trove.namespaces.contains(*target))
+            .map(|(alias, target)| (alias.clone(), target.clone()))
+            .collect();
+        trove
+    }
+
+    #[allow(dead_code)]
+    /// Get a copy of the trove collection with only the commands of `namespace`, e.g. to share them
+    ///
+    /// `namespace` may be a namespace alias. The version of the trove and aliases of the namespace are kept.
+    /// Returns an empty trove if the namespace has no commands. The trove itself is not changed
+    pub fn namespace_subset(&self, namespace: &str) -> Self {
+        let namespace = self.resolve_namespace_alias(namespace);
+        let commands: Vec<HoardCmd> = self
+            .commands
+            .iter()
+            .filter(|c| c.namespace == namespace)
+            .cloned()
+            .collect();
+        let mut trove = Self::from_commands(&commands);
+        trove.version.clone_from(&self.version);
+        trove.namespace_aliases = self
+            .namespace_aliases
+            .iter()
+            .filter(|(_, target)| trove.namespaces.contains(*target))
+            .map(|(alias, target)| (alias.clone(), target.clone()))
+            .collect();
+        trove
+    }
+
+    #[allow(dead_code)]
+    /// Export the `(namespace, name)` commands of `order` as a shell script typing out and running each of them
+    ///
+    /// Parameters are resolved to their defaults, see `Trove::to_demo_script_with`
+    pub fn to_demo_script(&self, order: &[(String, String)]) -> Result<String, TroveError> {
+        self.to_demo_script_with(order, &HashMap::new())
+    }
+
+    #[allow(dead_code)]
+    /// Export the `(namespace, name)` commands of `order` as a shell script typing out and running each of them
+    ///
+    /// Each command is echoed with a `$ ` prompt, followed by a pause, the command itself and another pause,
+    /// e.g. to record a demo with asciinema. The pause defaults to one second and can be set with `DEMO_PAUSE`.
+    /// Parameters are resolved to the sample values of `samples` by their name, falling back to their defaults.
+    /// Returns `TroveError::CommandNotFound` if a command of `order` is not in the trove
+    /// and `TroveError::MissingParameters` if a parameter has neither a sample value nor a default
+    pub fn to_demo_script_with(
+        &self,
+        order: &[(String, String)],
+        samples: &HashMap<String, String>,
+    ) -> Result<String, TroveError> {
+        let config = HoardConfig::default();
+        let mut script = String::from("#!/bin/sh\n[ -n \"$DEMO_PAUSE\" ] || DEMO_PAUSE=1\n");
+        for (namespace, name) in order {
+            let qualified_name = format!("{namespace}/{name}");
+            let resolved = self.resolve(&qualified_name, samples, &config)?;
+            let _ = writeln!(script, "\n# {qualified_name}");
+            let _ = writeln!(script, "printf '$ %s\\n' {}", shell_quote(&resolved));
+            script.push_str("sleep \"$DEMO_PAUSE\"\n");
+            let _ = writeln!(script, "{resolved}");
+            script.push_str("sleep \"$DEMO_PAUSE\"\n");
+        }
+        Ok(script)
+    }
+
+    #[allow(dead_code)]
+    /// Export all commands as recipes of a `justfile`
+    ///
+    /// Each command becomes a recipe named `namespace-name`, documented with its description.
+    /// Parameters become recipe parameters, keeping their declared defaults, e.g. `#port=8080!` becomes `port='8080'`.
+    /// Commands that can not be mapped to a recipe, e.g. because a parameter name is no valid `just` identifier,
+    /// are skipped and noted with a comment
+    pub fn to_justfile(&self) -> String {
+        let config = HoardConfig::default();
+        let mut justfile = String::new();
+        let mut recipes: Vec<String> = Vec::new();
+        for command in &self.commands {
+            let recipe = just_identifier(&format!("{}-{}", command.namespace, command.name));
+            let (token, ending_token) = command.parameter_tokens(&config);
+            let mut parameters: Vec<String> = Vec::new();
+            let mut body = command.command.replace("{{", "{{{{");
+            let mut unmapped = None;
+            for spec in command.parameter_specs(&token, &ending_token) {
+                if just_identifier(&spec.name) != spec.name || spec.name.contains('-') {
+                    unmapped = Some(format!("parameter '{}' is no valid identifier", spec.name));
+                    break;
+                }
+                body = body.replacen(&spec.raw, &format!("{{{{{}}}}}", spec.name), 1);
+                let parameter = spec.default.as_ref().map_or_else(
+                    || spec.name.clone(),
+                    |default| format!("{}={}", spec.name, just_string(default)),
+                );
+                if !parameters
+                    .iter()
+                    .any(|p| p.split('=').next() == Some(&spec.name))
+                {
+                    parameters.push(parameter);
+                }
+            }
+            if recipes.contains(&recipe) {
+                unmapped = Some(format!("recipe name '{recipe}' is already taken"));
+            }
+            if let Some(reason) = unmapped {
+                let _ = writeln!(
+                    justfile,
+                    "# Skipped '{}/{}': {reason}\n",
+                    command.namespace, command.name
+                );
+                continue;
+            }
+            if !command.description.is_empty() {
+                let _ = writeln!(justfile, "# {}", command.description.replace('\n', " "));
+            }
+            justfile.push_str(&recipe);
+            for parameter in &parameters {
+                let _ = write!(justfile, " {parameter}");
+            }
+            justfile.push_str(":\n");
+            for line in body.lines() {
+                let _ = writeln!(justfile, "    {line}");
+            }
+            justfile.push('\n');
+            recipes.push(recipe);
+        }
+        justfile
+    }
+}
+
+/// A code fence longer than any run of backticks in `code`, so the code can not end the block early
+fn markdown_fence(code: &str) -> String {
+    let longest_run = code
+        .split(|c| c != '`')
+        .map(str::len)
+        .max()
+        .unwrap_or_default();
+    "`".repeat(longest_run.max(2) + 1)
+}
+
+/// Turn `name` into a valid `just` identifier, replacing invalid characters with `-`
+fn just_identifier(name: &str) -> String {
+    let identifier: String = name
+        .chars()
+        .map(|c| {
+            if c.is_ascii_alphanumeric() || c == '_' || c == '-' {
+                c
+            } else {
+                '-'
+            }
+        })
+        .collect();
+    if identifier.starts_with(|c: char| c.is_ascii_alphabetic() || c == '_') {
+        identifier
+    } else {
+        format!("_{identifier}")
+    }
+}
+
+/// Quote `value` as a `just` string literal
+fn just_string(value: &str) -> String {
+    if value.contains('\'') {
+        format!("\"{}\"", value.replace('\\', "\\\\").replace('"', "\\\""))
+    } else {
+        format!("'{value}'")
+    }
+}
+
+/// Quote `arg` as a single quoted `sh` word
+fn shell_quote(arg: &str) -> String {
+    format!("'{}'", arg.replace('\'', r"'\''"))
+}
+
+/// Escape `arg` to be used within a double quoted argument of a desktop entry `Exec` key
+fn escape_exec_arg(arg: &str) -> String {
+    let mut escaped = String::with_capacity(arg.len());
+    for c in arg.chars() {
+        match c {
+            '"' | '`' | '$' | '\\' => {
+                escaped.push('\\');
+                escaped.push(c);
+            }
+            '%' => escaped.push_str("%%"),
+            _ => escaped.push(c),
+        }
+    }
+    escaped
+}
+
+impl HoardCmd {
+    #[allow(dead_code)]
+    /// Export the command as a freedesktop `.desktop` launcher entry running in a terminal
+    ///
+    /// The command runs in `sh`. The first parameter completing from files or dirs becomes a `%f` argument,
+    /// or a parameter named `url`/`uri` a `%u` argument. Launchers only support a single such argument,
+    /// so every other parameter is left in the command and noted with a comment
+    pub fn to_desktop_entry(&self) -> String {
+        let config = HoardConfig::default();
+        let (token, ending_token) = self.parameter_tokens(&config);
+        let mut exec_command = escape_exec_arg(&self.command);
+        let mut field_code = None;
+        let mut unsupported = Vec::new();
+        for spec in self.parameter_specs(&token, &ending_token) {
+            let code = match (&spec.completion, spec.name.to_lowercase().as_str()) {
+                (Some(CompletionSource::Files | CompletionSource::Dirs), _) => Some("%f"),
+                (_, "url" | "uri") => Some("%u"),
+                _ => None,
+            };
+            let raw = escape_exec_arg(&spec.raw);
+            match code {
+                Some(code) if field_code.is_none() => {
+                    exec_command = exec_command.replace(&raw, "\\\"\\$1\\\"");
+                    field_code = Some(code);
+                }
+                _ if exec_command.contains(&raw) && !unsupported.contains(&spec.name) => {
+                    unsupported.push(spec.name);
+                }
+                _ => {}
+            }
+        }
+
+        let mut exec = format!("sh -c \"{exec_command}\"");
+        if let Some(code) = field_code {
+            let _ = write!(exec, " sh {code}");
+        }
+        let mut entry = String::new();
+        for name in unsupported {
+            let _ = writeln!(
+                entry,
+                "# Unsupported parameter '{name}': launchers can not fill it in"
+            );
+        }
+        entry.push_str("[Desktop Entry]\nType=Application\n");
+        let _ = writeln!(entry, "Name={}", self.name);
+        let _ = writeln!(entry, "Exec={}", exec.replace('\\', "\\\\"));
+        if !self.description.is_empty() {
+            let _ = writeln!(entry, "Comment={}", self.description.replace('\n', " "));
+        }
+        entry.push_str("Terminal=true\n");
+        entry
+    }
+}
+
+#[cfg(test)]
+mod test_commands {
+    use super::*;
+
+    fn command(name: &str, namespace: &str, command: &str) -> HoardCmd {
+        HoardCmd::default()
+            .with_name(name)
+            .with_namespace(namespace)
+            .with_command(command)
+    }
+
+    #[test]
+    fn env_file_export() {
+        let trove = Trove::from_commands(&[
+            command("foo", "env", "export FOO=bar"),
+            command("path", "env", "PATH_EXTRA=\"/opt/bin\""),
+            command("ls", "env", "ls -la"),
+            command("other", "other", "export OTHER=1"),
+        ]);
+        let expected = "FOO=bar\nPATH_EXTRA=\"/opt/bin\"\n# Skipped 'ls': not an environment variable definition\n";
+        assert_eq!(expected, trove.to_env_file("env"));
+    }
+
+    #[test]
+    fn csv_round_trip() {
+        let trove = Trove::from_commands(&[
+            command("awk", "text", "awk -F, '{print $1, $2}'")
+                .with_description("Print the first \"two\" columns")
+                .with_tags_raw("csv,awk"),
+            command("multi", "shell", "echo a\necho b"),
+        ]);
+        let csv = trove.to_csv().unwrap();
+        assert!(csv.starts_with("name,namespace,command,description,tags\n"));
+        assert!(csv.contains("\"awk -F, '{print $1, $2}'\""));
+        assert!(csv.contains(",csv|awk\n"));
+
+        let imported = Trove::from_csv(&csv).unwrap();
+        assert_eq!(trove.commands, imported.commands);
+    }
+
+    #[test]
+    fn csv_import_requires_columns() {
+        let imported = Trove::from_csv("command,name,namespace\nls,list,shell\n").unwrap();
+        assert_eq!("ls", imported.commands[0].command);
+        assert!(imported.commands[0].tags.is_empty());
+        assert!(matches!(
+            Trove::from_csv("name,command\nlist,ls\n"),
+            Err(TroveError::Invalid(_))
+        ));
+    }
+
+    #[test]
+    fn markdown_cheat_sheet() {
+        let trove = Trove::from_commands(&[
+            command("status", "git", "git status"),
+            command("pods", "k8s", "kubectl get pods")
+                .with_description("List all pods")
+                .with_tags_raw("prod,k8s"),
+            command("log", "git", "git log --format='```%s'"),
+        ]);
+        let expected = "## git\n\n### log\n\n````sh\ngit log --format='```%s'\n````\n\n\
+            ### status\n\n```sh\ngit status\n```\n\n\
+            ## k8s\n\n### pods\n\nList all pods\n\n`prod` `k8s`\n\n```sh\nkubectl get pods\n```\n\n";
+        assert_eq!(expected, trove.to_markdown());
+    }
+
+    #[test]
+    fn env_file_unknown_namespace() {
+        let trove = Trove::from_commands(&[command("foo", "env", "export FOO=bar")]);
+        assert!(trove.to_env_file("unknown").is_empty());
+    }
+
+    #[test]
+    fn export_excluding_namespaces() {
+        let mut trove = Trove::from_commands(&[
+            command("token", "secrets", "export TOKEN=abc"),
+            command("status", "git", "git status"),
+        ]);
+        trove.set_namespace_alias("private", "secrets").unwrap();
+        trove.set_namespace_alias("g", "git").unwrap();
+
+        let shared = trove.export_excluding(&["private"]);
+        assert_eq!(vec!["git"], shared.namespaces());
+        assert!(shared.namespaces.contains("git"));
+        assert!(!shared.namespaces.contains("secrets"));
+        assert_eq!(1, shared.namespace_aliases.len());
+        // The original trove is not changed
+        assert_eq!(2, trove.commands.len());
+    }
+
+    #[test]
+    fn list_json_has_tag_arrays() {
+        let trove = Trove::from_commands(&[
+            command("status", "git", "git status").with_tags_raw("vcs,daily")
+        ]);
+        assert_eq!(trove.to_list_json(), trove.try_to_list_json().unwrap());
+        let json: serde_json::Value = serde_json::from_str(&trove.to_list_json()).unwrap();
+        assert_eq!("status", json[0]["name"]);
+        assert_eq!("git", json[0]["namespace"]);
+        assert_eq!(serde_json::json!(["vcs", "daily"]), json[0]["tags"]);
+        assert!(json[0].get("id").is_none());
+    }
+
+    #[test]
+    fn desktop_entry() {
+        let edit = command("edit", "tools", "vim #file:files! && echo \"$HOME\" #msg!")
+            .with_description("Edit a file");
+        let entry = edit.to_desktop_entry();
+        assert_eq!(
+            "# Unsupported parameter 'msg': launchers can not fill it in\n\
+             [Desktop Entry]\n\
+             Type=Application\n\
+             Name=edit\n\
+             Exec=sh -c \"vim \\\\\"\\\\$1\\\\\" && echo \\\\\"\\\\$HOME\\\\\" #msg!\" sh %f\n\
+             Comment=Edit a file\n\
+             Terminal=true\n",
+            entry
+        );
+
+        let entry = command("open", "web", "xdg-open #url! 100%").to_desktop_entry();
+        assert!(entry.contains("Exec=sh -c \"xdg-open \\\\\"\\\\$1\\\\\" 100%%\" sh %u\n"));
+        assert!(!entry.contains("Comment="));
+    }
+
+    #[test]
+    fn demo_script() {
+        let trove = Trove::from_commands(&[
+            command("status", "git", "git status"),
+            command("greet", "demo", "echo 'hello #name=world!'"),
+        ]);
+        let order = |names: &[(&str, &str)]| -> Vec<(String, String)> {
+            names
+                .iter()
+                .map(|(namespace, name)| (namespace.to_string(), name.to_string()))
+                .collect()
+        };
+        let script = trove
+            .to_demo_script(&order(&[("git", "status"), ("demo", "greet")]))
+            .unwrap();
+        assert!(script.starts_with("#!/bin/sh\n"));
+        assert!(
+            script.contains("printf '$ %s\\n' 'git status'\nsleep \"$DEMO_PAUSE\"\ngit status\n")
+        );
+        assert!(script.contains(r"printf '$ %s\n' 'echo '\''hello world'\'''"));
+
+        let samples = HashMap::from([("name".to_string(), "hoard".to_string())]);
+        let script = trove
+            .to_demo_script_with(&order(&[("demo", "greet")]), &samples)
+            .unwrap();
+        assert!(script.contains("\necho 'hello hoard'\n"));
+
+        assert!(matches!(
+            trove.to_demo_script(&order(&[("git", "missing")])),
+            Err(TroveError::CommandNotFound(_))
+        ));
+    }
+
+    #[test]
+    fn justfile_export() {
+        let mut deploy = command("deploy", "k8s", "kubectl apply -f #file! -n #ns=default!");
+        deploy.description = "apply a manifest".to_string();
+        let trove = Trove::from_commands(&[
+            deploy,
+            command("status", "git", "git status"),
+            command("odd", "git", "echo #some param!"),
+            command("template", "go", "echo '{{.Name}}'"),
+        ]);
+        let justfile = trove.to_justfile();
+        assert!(justfile.contains(
+            "# apply a manifest\nk8s-deploy file ns='default':\n    kubectl apply -f {{file}} -n {{ns}}\n"
+        ));
+        assert!(justfile.contains("git-status:\n    git status\n"));
+        assert!(
+            justfile.contains("# Skipped 'git/odd': parameter 'some param' is no valid identifier")
+        );
+        assert!(justfile.contains("    echo '{{{{.Name}}'\n"));
+    }
+
+    #[test]
+    fn namespace_subset() {
+        let mut trove = Trove::from_commands(&[
+            command("pods", "kubernetes", "kubectl get pods"),
+            command("status", "git", "git status"),
+        ]);
+        trove.version = "1.0.0".to_string();
+        trove.set_namespace_alias("k8s", "kubernetes").unwrap();
+
+        let subset = trove.namespace_subset("k8s");
+        assert_eq!(1, subset.commands.len());
+        assert_eq!(
+            vec!["kubernetes"],
+            subset.namespaces.iter().collect::<Vec<_>>()
+        );
+        assert_eq!("1.0.0", subset.version);
+        assert_eq!(1, subset.namespace_aliases.len());
+
+        let empty = trove.namespace_subset("missing");
+        assert!(empty.is_empty());
+        assert!(empty.namespaces.is_empty());
+    }
+}
@@ -0,0 +1,119 @@
+use anyhow::anyhow;
+use std::collections::HashSet;
+use std::{fs, path::Path};
+
+use crate::core::error::TroveError;
+use crate::core::trove::Trove;
+use crate::core::HoardCmd;
+
+impl Trove {
+    #[allow(dead_code)]
+    /// Load the ids of favorite commands from the sidecar file at `path`
+    ///
+    /// A missing file is treated as no favorites
+    pub fn load_favorites(&mut self, path: &Path) -> Result<(), TroveError> {
+        self.favorites = if path.exists() {
+            let f = fs::File::open(path)?;
+            serde_yaml::from_reader::<_, HashSet<String>>(f)?
+        } else {
+            HashSet::new()
+        };
+        Ok(())
+    }
+
+    #[allow(dead_code)]
+    /// Save the ids of favorite commands to the sidecar file at `path`
+    pub fn save_favorites(&self, path: &Path) -> Result<(), TroveError> {
+        let mut favorites: Vec<&String> = self.favorites.iter().collect();
+        favorites.sort();
+        fs::write(path, serde_yaml::to_string(&favorites)?)?;
+        Ok(())
+    }
+
+    #[allow(dead_code)]
+    /// Mark the command with the stable `id` as a favorite
+    ///
+    /// Returns `Err(anyhow::Error)` if no command with that id is in the trove
+    pub fn mark_favorite(&mut self, id: &str) -> Result<(), anyhow::Error> {
+        if self.get_command_by_id(id).is_none() {
+            return Err(anyhow!("Command not found [{}]", id));
+        }
+        self.favorites.insert(id.to_string());
+        Ok(())
+    }
+
+    #[allow(dead_code)]
+    /// Remove the command with the stable `id` from the favorites
+    ///
+    /// Returns `true` if the command was a favorite
+    pub fn unmark_favorite(&mut self, id: &str) -> bool {
+        self.favorites.remove(id)
+    }
+
+    #[allow(dead_code)]
+    /// Get all favorite commands of the trove collection
+    ///
+    /// Favorites referencing commands which are not part of the trove are ignored
+    pub fn favorites(&self) -> Vec<&HoardCmd> {
+        self.commands
+            .iter()
+            .filter(|c| self.favorites.contains(&c.id))
+            .collect()
+    }
+}
+
+#[cfg(test)]
+mod test_commands {
+    use super::*;
+
+    fn trove() -> Trove {
+        let command1 = HoardCmd::default()
+            .with_name("test1")
+            .with_namespace("test")
+            .with_command("echo 1");
+        let command2 = HoardCmd::default()
+            .with_name("test2")
+            .with_namespace("test")
+            .with_command("echo 2");
+        Trove::from_commands(&[command1, command2])
+    }
+
+    #[test]
+    fn mark_favorite() {
+        let mut trove = trove();
+        let id = trove.commands[1].id.clone();
+        assert!(trove.mark_favorite(&id).is_ok());
+        assert!(trove.mark_favorite("unknown").is_err());
+        let favorites = trove.favorites();
+        assert_eq!(1, favorites.len());
+        assert_eq!("test2", favorites[0].name);
+        assert!(trove.unmark_favorite(&id));
+        assert!(trove.favorites().is_empty());
+    }
+
+    #[test]
+    fn favorites_survive_reimport() {
+        let tmp_dir = tempfile::tempdir().unwrap();
+        let path = tmp_dir.path().join("favorites.yml");
+        let mut trove = trove();
+        let id = trove.commands[0].id.clone();
+        trove.mark_favorite(&id).unwrap();
+        trove.save_favorites(&path).unwrap();
+
+        // Favorites are not part of the trove file itself
+        let mut reloaded = Trove::load_trove_from_string(&trove.to_yaml());
+        assert!(reloaded.favorites().is_empty());
+        reloaded.load_favorites(&path).unwrap();
+        assert_eq!("test1", reloaded.favorites()[0].name);
+    }
+
+    #[test]
+    fn missing_favorites_file() {
+        let tmp_dir = tempfile::tempdir().unwrap();
+        let mut trove = trove();
+        assert!(trove
+            .load_favorites(&tmp_dir.path().join("missing.yml"))
+            .is_ok());
+        assert!(trove.favorites().is_empty());
+    }
+}
@@ -0,0 +1,167 @@
+use std::collections::{BTreeMap, HashMap};
+
+use crate::core::trove::Trove;
+use crate::core::HoardCmd;
+
+/// Lookup index over the commands of a trove, to avoid scanning all commands on repeated lookups
+///
+/// - `by_qualified_name`: Position of every command by namespace and name, for lookups in constant time
+/// - `by_name`: Positions of the commands by name, sorted for prefix lookups
+///
+/// The index borrows the trove, so it can not go stale: mutating the trove requires dropping the index
+/// and building a new one with `Trove::build_index` afterward
+#[allow(dead_code)]
+pub struct TroveIndex<'a> {
+    trove: &'a Trove,
+    by_qualified_name: HashMap<(String, &'a str), usize>,
+    by_name: BTreeMap<&'a str, Vec<usize>>,
+}
+
+impl<'a> TroveIndex<'a> {
+    /// Build the index over all commands of `trove`
+    pub fn new(trove: &'a Trove) -> Self {
+        let mut by_qualified_name = HashMap::with_capacity(trove.commands.len());
+        let mut by_name: BTreeMap<&str, Vec<usize>> = BTreeMap::new();
+        for (position, command) in trove.commands.iter().enumerate() {
+            by_qualified_name
+                .entry((
+                    Self::namespace_key(trove, &command.namespace),
+                    command.name.as_str(),
+                ))
+                .or_insert(position);
+            by_name.entry(&command.name).or_default().push(position);
+        }
+        Self {
+            trove,
+            by_qualified_name,
+            by_name,
+        }
+    }
+
+    /// The key of `namespace`, lowercase if namespaces are not case sensitive
+    fn namespace_key(trove: &Trove, namespace: &str) -> String {
+        if trove.options.case_sensitive_namespaces {
+            namespace.to_string()
+        } else {
+            namespace.to_lowercase()
+        }
+    }
+
+    #[allow(dead_code)]
+    /// Get the command `name` of `namespace`, which may be a namespace alias
+    pub fn lookup(&self, namespace: &str, name: &str) -> Option<&'a HoardCmd> {
+        let namespace = self.trove.resolve_namespace_alias(namespace);
+        self.by_qualified_name
+            .get(&(Self::namespace_key(self.trove, namespace), name))
+            .map(|position| &self.trove.commands[*position])
+    }
+
+    #[allow(dead_code)]
+    /// Get all commands whose name starts with `prefix`, sorted by name
+    ///
+    /// Commands with the same name keep their order in the trove
+    pub fn by_name_prefix(&self, prefix: &str) -> Vec<&'a HoardCmd> {
+        self.by_name
+            .range(prefix..)
+            .take_while(|(name, _)| name.starts_with(prefix))
+            .flat_map(|(_, positions)| positions.iter().map(|p| &self.trove.commands[*p]))
+            .collect()
+    }
+}
+
+impl Trove {
+    #[allow(dead_code)]
+    /// Build a `TroveIndex` for fast repeated lookups of commands
+    pub fn build_index(&self) -> TroveIndex<'_> {
+        TroveIndex::new(self)
+    }
+}
+
+#[cfg(test)]
+mod test_commands {
+    use super::*;
+    use std::time::{Duration, Instant};
+
+    fn trove(size: usize) -> Trove {
+        let commands: Vec<HoardCmd> = (0..size)
+            .map(|i| {
+                HoardCmd::default()
+                    .with_name(&format!("command{i}"))
+                    .with_namespace(&format!("namespace{}", i % 10))
+                    .with_command("echo test")
+            })
+            .collect();
+        Trove::from_commands(&commands)
+    }
+
+    #[test]
+    fn lookup_and_prefix() {
+        let mut trove = trove(20);
+        trove.set_namespace_alias("ns3", "namespace3").unwrap();
+        let index = trove.build_index();
+        assert_eq!("command13", index.lookup("ns3", "command13").unwrap().name);
+        assert!(index.lookup("namespace4", "command13").is_none());
+        assert!(index.lookup("namespace3", "missing").is_none());
+
+        let names: Vec<&str> = index
+            .by_name_prefix("command1")
+            .iter()
+            .map(|c| c.name.as_str())
+            .collect();
+        assert_eq!(11, names.len());
+        assert_eq!("command1", names[0]);
+        assert!(names[1..].windows(2).all(|w| w[0] < w[1]));
+        assert!(index.by_name_prefix("nope").is_empty());
+    }
+
+    /// Look up every fifth command of `trove` with the index and with linear scans, checking both find all of them
+    ///
+    /// Returns how long the linear scans and the index lookups took
+    fn time_lookups(trove: &Trove) -> (Duration, Duration) {
+        let wanted: Vec<(String, String)> = trove
+            .commands
+            .iter()
+            .step_by(5)
+            .map(|c| (c.namespace.clone(), c.name.clone()))
+            .collect();
+
+        let start = Instant::now();
+        let scanned = wanted
+            .iter()
+            .filter(|(namespace, name)| {
+                trove
+                    .commands
+                    .iter()
+                    .any(|c| &c.namespace == namespace && &c.name == name)
+            })
+            .count();
+        let linear = start.elapsed();
+
+        let index = trove.build_index();
+        let start = Instant::now();
+        let found = wanted
+            .iter()
+            .filter(|(namespace, name)| index.lookup(namespace, name).is_some())
+            .count();
+        let indexed = start.elapsed();
+
+        assert_eq!(wanted.len(), scanned);
+        assert_eq!(wanted.len(), found);
+        (linear, indexed)
+    }
+
+    #[test]
+    fn lookup_matches_linear_scan() {
+        time_lookups(&trove(500));
+    }
+
+    #[test]
+    #[ignore = "benchmark, depends on the load of the machine"]
+    fn bench_lookup_against_linear_scan() {
+        let (linear, indexed) = time_lookups(&trove(5000));
+        assert!(
+            indexed < linear,
+            "index lookups took {indexed:?}, linear scans took {linear:?}"
+        );
+    }
+}
@@ -0,0 +1,363 @@
+use std::collections::{HashMap, HashSet};
+
+use crate::config::HoardConfig;
+use crate::core::parameters::Parameterized;
+use crate::core::trove::Trove;
+use crate::core::HoardCmd;
+
+/// A single finding of `Trove::lint`
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct LintIssue {
+    /// The affected command as `namespace/name`, `None` for issues of the trove itself
+    pub command: Option<String>,
+    pub message: String,
+}
+
+/// Result of `Trove::lint` and `Trove::lint_fix`
+///
+/// - `fixed`: Issues that were fixed automatically. Always empty for `Trove::lint`
+/// - `remaining`: Issues that still need attention. For `Trove::lint` this includes auto-fixable issues
+#[derive(Debug, Clone, Default, PartialEq, Eq)]
+pub struct LintReport {
+    pub fixed: Vec<LintIssue>,
+    pub remaining: Vec<LintIssue>,
+}
+
+impl LintReport {
+    #[allow(dead_code)]
+    /// Check if no issues remain
+    pub const fn is_clean(&self) -> bool {
+        self.remaining.is_empty()
+    }
+
+    fn push(&mut self, fixed: bool, command: Option<&HoardCmd>, message: String) {
+        let issue = LintIssue {
+            command: command.map(|c| format!("{}/{}", c.namespace, c.name)),
+            message,
+        };
+        if fixed {
+            self.fixed.push(issue);
+        } else {
+            self.remaining.push(issue);
+        }
+    }
+}
+
+/// Extract the delimiter of a here-document starting right after `<<`
+/// Supports `<<EOF`, `<<-EOF`, `<<'EOF'` and `<<"EOF"`
+fn heredoc_delimiter(rest: &str) -> Option<String> {
+    let rest = rest.strip_prefix('-').unwrap_or(rest).trim_start();
+    let delimiter: String = rest
+        .chars()
+        .take_while(|c| !c.is_whitespace() && !matches!(c, ';' | '|' | '&' | '<' | '>' | ')'))
+        .filter(|c| *c != '\'' && *c != '"')
+        .collect();
+    if delimiter.is_empty() {
+        None
+    } else {
+        Some(delimiter)
+    }
+}
+
+impl HoardCmd {
+    /// Lightweight syntax check of the command string
+    ///
+    /// This is no full shell parser. It checks for
+    /// - balanced single quotes, double quotes and backticks
+    /// - matched parentheses outside of quotes
+    /// - terminated here-documents
+    ///
+    /// Returns a list of warnings, which is empty if no issue was found
+    pub fn shell_lint(&self) -> Vec<String> {
+        let mut warnings = Vec::new();
+        let mut in_single = false;
+        let mut in_double = false;
+        let mut in_backtick = false;
+        let mut parentheses: usize = 0;
+        let mut heredocs = Vec::new();
+
+        let mut chars = self.command.char_indices();
+        while let Some((i, c)) = chars.next() {
+            match c {
+                '\\' if !in_single => {
+                    // Skip the escaped character
+                    chars.next();
+                }
+                '\'' if !in_double && !in_backtick => in_single = !in_single,
+                '"' if !in_single => in_double = !in_double,
+                '`' if !in_single => in_backtick = !in_backtick,
+                '(' if !in_single && !in_double => parentheses += 1,
+                ')' if !in_single && !in_double => {
+                    if parentheses == 0 {
+                        warnings.push(format!("Unmatched closing parenthesis at position {i}"));
+                    } else {
+                        parentheses -= 1;
+                    }
+                }
+                '<' if !in_single && !in_double => {
+                    let rest = &self.command[i + 1..];
+                    // `<<<` is a here-string and needs no terminating line
+                    if rest.starts_with('<') && !rest.starts_with("<<") {
+                        chars.next();
+                        if let Some(delimiter) = heredoc_delimiter(&rest[1..]) {
+                            heredocs.push(delimiter);
+                        }
+                    } else if rest.starts_with("<<") {
+                        chars.next();
+                        chars.next();
+                    }
+                }
+                _ => {}
+            }
+        }
+
+        if in_single {
+            warnings.push("Unbalanced single quote".to_string());
+        }
+        if in_double {
+            warnings.push("Unbalanced double quote".to_string());
+        }
+        if in_backtick {
+            warnings.push("Unbalanced backtick".to_string());
+        }
+        if parentheses > 0 {
+            warnings.push(format!("{parentheses} unclosed parenthesis"));
+        }
+        for delimiter in heredocs {
+            let terminated = self
+                .command
+                .lines()
+                .skip(1)
+                .any(|line| line.trim_start_matches('\t') == delimiter);
+            if !terminated {
+                warnings.push(format!("Unterminated here-document '{delimiter}'"));
+            }
+        }
+        warnings
+    }
+}
+
+/// Trim a field in place if `fix` is set. Returns `true` if the field has surrounding whitespace
+fn trim_field(field: &mut String, fix: bool) -> bool {
+    let trimmed = field.trim();
+    if trimmed.len() == field.len() {
+        return false;
+    }
+    if fix {
+        *field = trimmed.to_string();
+    }
+    true
+}
+
+impl Trove {
+    #[allow(dead_code)]
+    /// Check the trove collection for issues without changing it
+    ///
+    /// See `Trove::lint_fix` for the performed checks. All issues are reported as remaining
+    pub fn lint(&self, config: &HoardConfig) -> LintReport {
+        self.clone().check(config, false)
+    }
+
+    #[allow(dead_code)]
+    /// Check the trove collection for issues and fix the ones that can be fixed automatically
+    ///
+    /// Fixed automatically:
+    /// - whitespace around names, namespaces, commands, descriptions and tags
+    /// - duplicate tags
+    /// - namespaces missing from or orphaned in the namespace set
+    ///
+    /// Only reported:
+    /// - invalid commands
+    /// - commands with the same name within a namespace
+    /// - command bodies stored in more than one namespace
+    /// - unterminated parameters
+    /// - shell syntax warnings, see `HoardCmd::shell_lint`
+    pub fn lint_fix(&mut self, config: &HoardConfig) -> LintReport {
+        self.check(config, true)
+    }
+
+    fn check(&mut self, config: &HoardConfig, fix: bool) -> LintReport {
+        let mut report = LintReport::default();
+
+        for position in 0..self.commands.len() {
+            let mut command = self.commands[position].clone();
+            let mut trimmed = Vec::new();
+            for (field, value) in [
+                ("name", &mut command.name),
+                ("namespace", &mut command.namespace),
+                ("command", &mut command.command),
+                ("description", &mut command.description),
+            ] {
+                if trim_field(value, fix) {
+                    trimmed.push(field);
+                }
+            }
+            if command
+                .tags
+                .iter_mut()
+                .fold(false, |any, tag| trim_field(tag, fix) | any)
+            {
+                trimmed.push("tags");
+            }
+            if !trimmed.is_empty() {
+                let message = format!("Surrounding whitespace in {}", trimmed.join(", "));
+                report.push(fix, Some(&command), message);
+            }
+            let mut unique_tags = HashSet::new();
+            if !command
+                .tags
+                .iter()
+                .all(|tag| unique_tags.insert(tag.trim()))
+            {
+                if fix {
+                    command.mut_dedupe_tags();
+                }
+                report.push(fix, Some(&command), "Duplicate tags".to_string());
+            }
+            if fix && command != self.commands[position] {
+                self.replace_at(position, command);
+            }
+        }
+
+        let referenced: HashSet<String> =
+            self.commands.iter().map(|c| c.namespace.clone()).collect();
+        let mut drift: Vec<String> = referenced
+            .symmetric_difference(&self.namespaces)
+            .map(|namespace| {
+                if referenced.contains(namespace) {
+                    format!("Namespace '{namespace}' is missing from the namespace set")
+                } else {
+                    format!("Namespace '{namespace}' has no commands")
+                }
+            })
+            .collect();
+        drift.sort();
+        for message in drift {
+            report.push(fix, None, message);
+        }
+        if fix {
+            self.rebuild_namespaces();
+        }
+
+        let mut seen: HashMap<(&str, &str), usize> = HashMap::new();
+        for command in &self.commands {
+            *seen.entry((&command.namespace, &command.name)).or_insert(0) += 1;
+        }
+        for command in &self.commands {
+            if !command.is_valid() {
+                report.push(false, Some(command), "Invalid command".to_string());
+            }
+            if seen.get(&(command.namespace.as_str(), command.name.as_str())) > Some(&1) {
+                report.push(false, Some(command), "Duplicate command name".to_string());
+            }
+            let (token, ending_token) = command.parameter_tokens(config);
+            if command.get_parameter_count(&token)
+                > command.parameter_specs(&token, &ending_token).len()
+            {
+                report.push(false, Some(command), "Unterminated parameter".to_string());
+            }
+            for warning in command.shell_lint() {
+                report.push(false, Some(command), warning);
+            }
+        }
+        for (body, locations) in self.cross_namespace_duplicates(true) {
+            let locations: Vec<String> = locations
+                .iter()
+                .map(|(namespace, name)| format!("{namespace}/{name}"))
+                .collect();
+            let message = format!("'{body}' is stored as {}", locations.join(", "));
+            report.push(false, None, message);
+        }
+        report
+    }
+}
+
+#[cfg(test)]
+mod test_commands {
+    use super::*;
+
+    fn lint(command: &str) -> Vec<String> {
+        HoardCmd::default().with_command(command).shell_lint()
+    }
+
+    #[test]
+    fn valid_commands() {
+        assert!(lint("echo 'hello world' \"$HOME\"").is_empty());
+        assert!(lint("echo \"it's fine\"").is_empty());
+        assert!(lint("echo $(date) `whoami` \\\"").is_empty());
+        assert!(lint("cat <<EOF\nhello\nEOF").is_empty());
+        assert!(lint("cat <<-'END'\n\thello\n\tEND").is_empty());
+        assert!(lint("grep foo <<< \"$bar\"").is_empty());
+        assert!(lint("echo '(' \")\"").is_empty());
+    }
+
+    #[test]
+    fn unbalanced_quotes() {
+        assert_eq!(vec!["Unbalanced single quote"], lint("echo 'hello"));
+        assert_eq!(vec!["Unbalanced double quote"], lint("echo \"hello"));
+        assert_eq!(vec!["Unbalanced backtick"], lint("echo `hello"));
+    }
+
+    #[test]
+    fn unmatched_parentheses() {
+        assert_eq!(vec!["1 unclosed parenthesis"], lint("echo $(date"));
+        assert_eq!(
+            vec!["Unmatched closing parenthesis at position 9"],
+            lint("echo date)")
+        );
+    }
+
+    #[test]
+    fn unterminated_heredoc() {
+        assert_eq!(
+            vec!["Unterminated here-document 'EOF'"],
+            lint("cat <<EOF\nhello")
+        );
+    }
+
+    #[test]
+    fn trove_lint_separates_fixed_and_remaining() {
+        let config = HoardConfig::default();
+        let messy = HoardCmd::default()
+            .with_name("messy")
+            .with_namespace("test")
+            .with_command(" echo #name ")
+            .with_tags(vec!["a".to_string(), " a".to_string()]);
+        let broken = HoardCmd::default()
+            .with_name("broken")
+            .with_namespace("test")
+            .with_command("echo 'oops");
+        let mut trove = Trove::from_commands(&[messy, broken]);
+        trove.namespaces.insert("orphan".to_string());
+
+        let report = trove.lint(&config);
+        assert!(report.fixed.is_empty());
+        assert_eq!(5, report.remaining.len());
+
+        let report = trove.lint_fix(&config);
+        let fixed: Vec<&str> = report.fixed.iter().map(|i| i.message.as_str()).collect();
+        assert_eq!(
+            vec![
+                "Surrounding whitespace in command, tags",
+                "Duplicate tags",
+                "Namespace 'orphan' has no commands"
+            ],
+            fixed
+        );
+        let remaining: Vec<&str> = report
+            .remaining
+            .iter()
+            .map(|i| i.message.as_str())
+            .collect();
+        assert_eq!(
+            vec!["Unterminated parameter", "Unbalanced single quote"],
+            remaining
+        );
+        assert_eq!(Some("test/messy".to_string()), report.remaining[0].command);
+
+        assert_eq!("echo #name", trove.commands[0].command);
+        assert_eq!(vec!["a"], trove.commands[0].tags);
+        assert!(!trove.namespaces.contains("orphan"));
+        assert!(trove.lint_fix(&config).fixed.is_empty());
+    }
+}
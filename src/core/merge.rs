@@ -0,0 +1,665 @@
+use std::cmp::Ordering;
+use std::collections::HashMap;
+
+use crate::core::diff::unified_diff;
+use crate::core::events::TroveEvent;
+use crate::core::trove::Trove;
+use crate::core::{generate_id, HoardCmd};
+
+/// Strategy to resolve name collisions when merging two troves
+#[allow(dead_code)]
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum MergeStrategy {
+    /// Keep the local command, ignoring the incoming one
+    KeepLocal,
+    /// Replace the local command with the incoming one
+    KeepIncoming,
+    /// Keep both commands, the incoming one is added with a random name suffix
+    KeepBoth,
+    /// Keep whichever command is better documented.
+    ///
+    /// Commands are compared by
+    /// 1. having a non-empty description
+    /// 2. the number of tags
+    /// 3. the latest `modified` timestamp
+    ///
+    /// If both commands are equal in all three, the local command is kept
+    PreferRicher,
+    /// Keep the local command, but combine the usage statistics of both commands
+    ///
+    /// `last_used` is set to the later of both. `usage_count` is combined as configured by `UsageMerge`
+    CombineUsage(UsageMerge),
+}
+
+/// How `MergeStrategy::CombineUsage` combines the `usage_count` of colliding commands
+#[allow(dead_code)]
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum UsageMerge {
+    /// Keep the higher count. Safe for troves that were synced from each other before,
+    /// as usages both troves already share are not counted twice
+    Max,
+    /// Add both counts. Only correct if the troves never shared usages, e.g. on a first sync of two devices
+    Sum,
+}
+
+/// Changes of a command that was replaced when merging, as unified diffs, see `unified_diff`
+///
+/// - `namespace`, `name`: The replaced command
+/// - `command`: Diff of the command string, `None` if it did not change
+/// - `description`: Diff of the description, `None` if it did not change
+#[allow(dead_code)]
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct CommandDiff {
+    pub namespace: String,
+    pub name: String,
+    pub command: Option<String>,
+    pub description: Option<String>,
+}
+
+impl CommandDiff {
+    fn between(local: &HoardCmd, incoming: &HoardCmd) -> Self {
+        Self {
+            namespace: local.namespace.clone(),
+            name: local.name.clone(),
+            command: unified_diff(&local.command, &incoming.command),
+            description: unified_diff(&local.description, &incoming.description),
+        }
+    }
+}
+
+/// Outcome of `Trove::merge_trove_report`
+///
+/// Commands are listed by their qualified name, `namespace/name`
+/// - `changed`: If the trove changed
+/// - `added`: Incoming commands without a local counterpart
+/// - `overwritten`: Local commands replaced by the incoming command
+/// - `renamed`: Incoming commands added next to the local command with a new name, as `(name, new name)`
+/// - `skipped`: Incoming commands ignored in favor of the local command, e.g. because they are identical
+/// - `removed`: Local commands removed by incoming tombstones, only set by `Trove::apply_patch`
+/// - `diffs`: The changes of every overwritten command
+#[allow(dead_code)]
+#[derive(Debug, Clone, Default, PartialEq, Eq)]
+pub struct MergeReport {
+    pub changed: bool,
+    pub added: Vec<String>,
+    pub overwritten: Vec<String>,
+    pub renamed: Vec<(String, String)>,
+    pub skipped: Vec<String>,
+    pub removed: Vec<String>,
+    pub diffs: Vec<CommandDiff>,
+}
+
+impl MergeReport {
+    #[allow(dead_code)]
+    /// Summarize the report in a line, e.g. `12 added, 3 overwritten, 1 renamed, 0 skipped, 0 removed`
+    pub fn summary(&self) -> String {
+        format!(
+            "{} added, {} overwritten, {} renamed, {} skipped, {} removed",
+            self.added.len(),
+            self.overwritten.len(),
+            self.renamed.len(),
+            self.skipped.len(),
+            self.removed.len()
+        )
+    }
+}
+
+/// The qualified name of `command`, `namespace/name`
+fn qualified_name(command: &HoardCmd) -> String {
+    format!("{}/{}", command.namespace, command.name)
+}
+
+/// Compare how well documented two commands are, see `MergeStrategy::PreferRicher`
+fn compare_richness(a: &HoardCmd, b: &HoardCmd) -> Ordering {
+    (!a.description.is_empty())
+        .cmp(&!b.description.is_empty())
+        .then_with(|| a.tags.len().cmp(&b.tags.len()))
+        .then_with(|| a.modified.cmp(&b.modified))
+}
+
+/// Translate `tags` with `tag_map`, dropping duplicates the mapping produces
+fn map_tags(
+    tags: &[String],
+    tag_map: &HashMap<String, String>,
+    keep_unmapped: bool,
+) -> Vec<String> {
+    let mut mapped: Vec<String> = Vec::new();
+    for tag in tags {
+        let tag = match tag_map.get(tag) {
+            Some(mapped_tag) => mapped_tag,
+            None if keep_unmapped => tag,
+            None => continue,
+        };
+        if !mapped.contains(tag) {
+            mapped.push(tag.clone());
+        }
+    }
+    mapped
+}
+
+impl MergeStrategy {
+    /// Decide if `incoming` should replace `local` on a collision
+    fn prefers_incoming(self, local: &HoardCmd, incoming: &HoardCmd) -> bool {
+        match self {
+            Self::KeepIncoming => true,
+            Self::PreferRicher => compare_richness(incoming, local) == Ordering::Greater,
+            Self::KeepLocal | Self::KeepBoth | Self::CombineUsage(_) => false,
+        }
+    }
+}
+
+impl Trove {
+    #[allow(dead_code)]
+    /// Merge all commands of `other` into the trove collection, resolving name collisions with `strategy`
+    ///
+    /// Returns `true` if the trove changed
+    pub fn merge_trove_with_strategy(&mut self, other: &Self, strategy: MergeStrategy) -> bool {
+        self.merge_trove_report(other, strategy).changed
+    }
+
+    #[allow(dead_code)]
+    /// Merge all commands of `other` into the trove collection like `merge_trove_with_strategy`, reporting the changes
+    ///
+    /// The report lists what happened to every incoming command, see `MergeReport`.
+    /// It holds a line-level diff of the command and description of every overwritten command,
+    /// to review what a merge changed. Collisions merged with `MergeStrategy::CombineUsage` are listed as skipped,
+    /// as the local command is kept
+    pub fn merge_trove_report(&mut self, other: &Self, strategy: MergeStrategy) -> MergeReport {
+        self.merge_commands(other, strategy, false)
+    }
+
+    #[allow(dead_code)]
+    /// Apply a patch trove created by `changes_against`, reporting the changes like `merge_trove_report`
+    ///
+    /// Tombstones of the patch remove the matching local command, all other commands are merged with `MergeStrategy::KeepIncoming`.
+    /// This is the only merge applying tombstones, so importing a trove with deleted commands never removes local commands
+    pub fn apply_patch(&mut self, patch: &Self) -> MergeReport {
+        self.merge_commands(patch, MergeStrategy::KeepIncoming, true)
+    }
+
+    fn merge_commands(
+        &mut self,
+        other: &Self,
+        strategy: MergeStrategy,
+        apply_tombstones: bool,
+    ) -> MergeReport {
+        let mut report = MergeReport::default();
+        for incoming in &other.commands {
+            if apply_tombstones && incoming.is_deleted {
+                if self.remove_tombstoned(incoming) {
+                    report.changed = true;
+                    report.removed.push(qualified_name(incoming));
+                }
+                continue;
+            }
+            match (self.get_command_collision(incoming), strategy) {
+                // Identical commands of another device still carry usage statistics to combine
+                (Some(local), MergeStrategy::CombineUsage(usage_merge)) => {
+                    report.changed |= self.combine_usage(&local, incoming, usage_merge);
+                    report.skipped.push(qualified_name(incoming));
+                }
+                (Some(local), _) if local == *incoming => {
+                    report.skipped.push(qualified_name(incoming));
+                }
+                (Some(local), strategy) => match strategy {
+                    MergeStrategy::KeepBoth => {
+                        // The kept copy is a new command, so it must not share the id of the local command
+                        let renamed = self
+                            .with_unique_name_suffix(incoming)
+                            .with_id(&generate_id());
+                        report.changed = true;
+                        report
+                            .renamed
+                            .push((qualified_name(incoming), qualified_name(&renamed)));
+                        self.emit(|| TroveEvent::Added {
+                            command: renamed.clone(),
+                        });
+                        self.commands.push(renamed);
+                    }
+                    _ if strategy.prefers_incoming(&local, incoming)
+                        && self.replace_command(&local, incoming.clone()) =>
+                    {
+                        report.changed = true;
+                        report.overwritten.push(qualified_name(&local));
+                        report.diffs.push(CommandDiff::between(&local, incoming));
+                    }
+                    _ => report.skipped.push(qualified_name(incoming)),
+                },
+                (None, _) => {
+                    if matches!(self.add_command(incoming.clone(), true), Ok(true)) {
+                        report.changed = true;
+                        report.added.push(qualified_name(incoming));
+                    }
+                }
+            }
+        }
+        report
+    }
+
+    #[allow(dead_code)]
+    /// Merge all commands of `other` into the trove collection with `namespace_prefix` prepended to their namespace
+    ///
+    /// Keeps imported commands apart from local commands of the same namespace, e.g. `git` is imported as `shared/git`.
+    /// Namespaces already starting with the prefix are kept, so importing a trove again does not prefix it twice.
+    /// Collisions are resolved like `merge_trove`, the incoming command replaces the local one
+    pub fn merge_trove_with_prefix(&mut self, other: &Self, namespace_prefix: &str) -> MergeReport {
+        let prefixed: Vec<HoardCmd> = other
+            .commands
+            .iter()
+            .map(|c| {
+                let mut prefixed = c.clone();
+                if !prefixed.namespace.starts_with(namespace_prefix) {
+                    prefixed.namespace = format!("{namespace_prefix}{}", c.namespace);
+                }
+                prefixed
+            })
+            .collect();
+        self.merge_trove_report(&Self::from_commands(&prefixed), MergeStrategy::KeepIncoming)
+    }
+
+    #[allow(dead_code)]
+    /// Get a patch trove with all commands that are new or modified compared to `base`
+    ///
+    /// Commands of `base` that are missing in this trove are added as tombstones, flagged with `is_deleted`.
+    /// Applying the patch to `base` with `apply_patch` applies all changes, including the removals.
+    pub fn changes_against(&self, base: &Self) -> Self {
+        let mut changes: Vec<HoardCmd> = self
+            .commands
+            .iter()
+            .filter(|c| base.get_command_collision(c).is_none_or(|b| b != **c))
+            .cloned()
+            .collect();
+        let tombstones = base
+            .commands
+            .iter()
+            .filter(|b| self.get_command_collision(b).is_none())
+            .map(|b| {
+                let mut tombstone = b.clone();
+                tombstone.mut_set_deleted(true);
+                tombstone
+            });
+        changes.extend(tombstones);
+        Self::from_commands(&changes)
+    }
+
+    #[allow(dead_code)]
+    /// Merge `other` into a copy of the trove collection, letting `resolve` decide every conflict
+    ///
+    /// A conflict is a command with the same namespace and name in both troves that differs in its content.
+    /// `resolve` is called with the local and the incoming command and returns the command to keep.
+    /// Commands only present in `other` are added without calling `resolve`
+    pub fn merge_interactive(
+        &self,
+        other: &Self,
+        mut resolve: impl FnMut(&HoardCmd, &HoardCmd) -> HoardCmd,
+    ) -> Self {
+        let mut merged = self.clone();
+        for incoming in &other.commands {
+            match merged.get_command_collision(incoming) {
+                Some(local) if local != *incoming => {
+                    let resolved = resolve(&local, incoming);
+                    merged.add_namespace(&resolved.namespace);
+                    merged.replace_command(&local, resolved);
+                }
+                Some(_) => {}
+                None => {
+                    merged.add_namespace(&incoming.namespace);
+                    merged.commands.push(incoming.clone());
+                }
+            }
+        }
+        merged
+    }
+
+    #[allow(dead_code)]
+    /// Import all commands of `other`, translating their tags with `tag_map` first
+    ///
+    /// `tag_map` maps the categories of the source tool to tag names of this trove.
+    /// Categories missing from `tag_map` are kept as they are if `keep_unmapped` is set, otherwise they are dropped.
+    /// Returns `true` if the trove changed
+    pub fn merge_trove_with_tag_map(
+        &mut self,
+        other: &Self,
+        tag_map: &HashMap<String, String>,
+        keep_unmapped: bool,
+    ) -> bool {
+        let commands: Vec<HoardCmd> = other
+            .commands
+            .iter()
+            .map(|c| {
+                let mut command = c.clone();
+                command.tags = map_tags(&c.tags, tag_map, keep_unmapped);
+                command
+            })
+            .collect();
+        let mut mapped = other.clone();
+        mapped.commands = commands;
+        self.merge_trove(&mapped)
+    }
+
+    /// Update the usage statistics of `local` with the ones of `incoming`. Returns `true` if they changed
+    fn combine_usage(
+        &mut self,
+        local: &HoardCmd,
+        incoming: &HoardCmd,
+        usage_merge: UsageMerge,
+    ) -> bool {
+        let Some(position) = self
+            .commands
+            .iter()
+            .position(|c| c.namespace == local.namespace && c.name == local.name)
+        else {
+            return false;
+        };
+        let mut command = self.commands[position].clone();
+        command.usage_count = match usage_merge {
+            UsageMerge::Max => command.usage_count.max(incoming.usage_count),
+            UsageMerge::Sum => command.usage_count + incoming.usage_count,
+        };
+        command.last_used = command.last_used.max(incoming.last_used);
+        let current = &self.commands[position];
+        if command.usage_count == current.usage_count && command.last_used == current.last_used {
+            return false;
+        }
+        self.replace_at(position, command);
+        true
+    }
+
+    /// Replace `local` with `incoming` in place. Returns `false` if `local` is not part of the trove
+    fn replace_command(&mut self, local: &HoardCmd, incoming: HoardCmd) -> bool {
+        let Some(position) = self
+            .commands
+            .iter()
+            .position(|c| c.namespace == local.namespace && c.name == local.name)
+        else {
+            return false;
+        };
+        self.replace_at(position, incoming);
+        true
+    }
+}
+
+#[cfg(test)]
+mod test_commands {
+    use super::*;
+    use std::time::{Duration, SystemTime};
+
+    fn command(description: &str, tags: &str) -> HoardCmd {
+        HoardCmd::default()
+            .with_name("test")
+            .with_namespace("test")
+            .with_command("echo test")
+            .with_description(description)
+            .with_tags_raw(tags)
+    }
+
+    fn merged(local: HoardCmd, incoming: HoardCmd) -> HoardCmd {
+        let mut trove = Trove::from_commands(&[local]);
+        trove.merge_trove_with_strategy(
+            &Trove::from_commands(&[incoming]),
+            MergeStrategy::PreferRicher,
+        );
+        assert_eq!(1, trove.commands.len());
+        trove.commands[0].clone()
+    }
+
+    #[test]
+    fn changes_against_base() {
+        let unchanged = command("unchanged", "").with_name("unchanged");
+        let modified = command("old", "").with_name("modified");
+        let removed = command("removed", "").with_name("removed");
+        let base = Trove::from_commands(&[unchanged.clone(), modified.clone(), removed]);
+
+        let added = command("added", "").with_name("added");
+        let local = Trove::from_commands(&[unchanged, modified.with_description("new"), added]);
+
+        let patch = local.changes_against(&base);
+        let names: Vec<(&str, bool)> = patch
+            .commands
+            .iter()
+            .map(|c| (c.name.as_str(), c.is_deleted))
+            .collect();
+        assert_eq!(
+            vec![("modified", false), ("added", false), ("removed", true)],
+            names
+        );
+
+        // Applying the patch to the base removes the tombstoned command
+        let mut merged = base;
+        let report = merged.apply_patch(&patch);
+        assert_eq!(vec!["test/removed"], report.removed);
+        assert!(merged.commands.iter().all(|c| c.name != "removed"));
+        assert!(merged.commands.iter().any(|c| c.name == "added"));
+        assert!(merged.commands.iter().any(|c| c.description == "new"));
+    }
+
+    #[test]
+    fn prefer_richer_keeps_description() {
+        let result = merged(command("", "a,b,c"), command("documented", ""));
+        assert_eq!("documented", result.description);
+        let result = merged(command("documented", ""), command("", "a,b,c"));
+        assert_eq!("documented", result.description);
+    }
+
+    #[test]
+    fn prefer_richer_keeps_more_tags() {
+        let result = merged(command("local", "a"), command("incoming", "a,b"));
+        assert_eq!("incoming", result.description);
+    }
+
+    #[test]
+    fn prefer_richer_breaks_ties_by_modified() {
+        let mut local = command("local", "a");
+        let mut incoming = command("incoming", "b");
+        local.modified = SystemTime::UNIX_EPOCH + Duration::from_secs(20);
+        incoming.modified = SystemTime::UNIX_EPOCH + Duration::from_secs(10);
+        assert_eq!("local", merged(local.clone(), incoming.clone()).description);
+
+        incoming.modified = SystemTime::UNIX_EPOCH + Duration::from_secs(30);
+        assert_eq!("incoming", merged(local.clone(), incoming).description);
+
+        // Complete tie keeps the local command
+        let mut same = command("same", "a");
+        same.modified = local.modified;
+        let mut local_same = command("same", "b");
+        local_same.modified = local.modified;
+        assert_eq!(vec!["b".to_string()], merged(local_same, same).tags);
+    }
+
+    #[test]
+    fn merge_with_tag_map() {
+        let tag_map: HashMap<String, String> = [
+            ("kubernetes".to_string(), "k8s".to_string()),
+            ("cluster".to_string(), "k8s".to_string()),
+        ]
+        .into_iter()
+        .collect();
+        let other = Trove::from_commands(&[command("", "kubernetes,cluster,misc")]);
+
+        let mut trove = Trove::default();
+        assert!(trove.merge_trove_with_tag_map(&other, &tag_map, true));
+        assert_eq!(vec!["k8s", "misc"], trove.commands[0].tags);
+
+        let mut trove = Trove::default();
+        assert!(trove.merge_trove_with_tag_map(&other, &tag_map, false));
+        assert_eq!(vec!["k8s"], trove.commands[0].tags);
+        // The source trove is not changed
+        assert_eq!(3, other.commands[0].tags.len());
+    }
+
+    #[test]
+    fn merge_interactive_calls_resolver_for_conflicts_only() {
+        let local = Trove::from_commands(&[
+            command("local", "").with_name("conflict"),
+            command("same", "").with_name("same"),
+        ]);
+        let other = Trove::from_commands(&[
+            command("incoming", "").with_name("conflict"),
+            command("same", "").with_name("same"),
+            command("new", "").with_name("new"),
+        ]);
+
+        let mut conflicts = Vec::new();
+        let merged = local.merge_interactive(&other, |l, i| {
+            conflicts.push(l.name.clone());
+            i.clone()
+        });
+        assert_eq!(vec!["conflict"], conflicts);
+        assert_eq!(3, merged.commands.len());
+        assert_eq!("incoming", merged.commands[0].description);
+        // The local trove is left untouched
+        assert_eq!("local", local.commands[0].description);
+    }
+
+    #[test]
+    fn combine_usage() {
+        let mut local = command("local", "");
+        local.usage_count = 10;
+        local.last_used = SystemTime::UNIX_EPOCH + Duration::from_secs(10);
+        let mut incoming = command("incoming", "");
+        incoming.usage_count = 3;
+        incoming.last_used = SystemTime::UNIX_EPOCH + Duration::from_secs(20);
+        let other = Trove::from_commands(&[incoming]);
+
+        let mut trove = Trove::from_commands(&[local.clone()]);
+        let strategy = MergeStrategy::CombineUsage(UsageMerge::Max);
+        assert!(trove.merge_trove_with_strategy(&other, strategy));
+        assert_eq!(10, trove.commands[0].usage_count);
+        assert_eq!(
+            SystemTime::UNIX_EPOCH + Duration::from_secs(20),
+            trove.commands[0].last_used
+        );
+        // The content of the local command is kept
+        assert_eq!("local", trove.commands[0].description);
+        assert!(!trove.merge_trove_with_strategy(&other, strategy));
+
+        let mut trove = Trove::from_commands(&[local]);
+        trove.merge_trove_with_strategy(&other, MergeStrategy::CombineUsage(UsageMerge::Sum));
+        assert_eq!(13, trove.commands[0].usage_count);
+    }
+
+    #[test]
+    fn combine_usage_of_identical_commands() {
+        let mut local = command("same", "");
+        local.usage_count = 10;
+        let mut incoming = command("same", "");
+        incoming.usage_count = 3;
+        assert_eq!(local, incoming);
+        let other = Trove::from_commands(&[incoming]);
+
+        let mut trove = Trove::from_commands(&[local]);
+        let report = trove.merge_trove_report(&other, MergeStrategy::CombineUsage(UsageMerge::Sum));
+        assert!(report.changed);
+        assert_eq!(vec!["test/test"], report.skipped);
+        assert_eq!(13, trove.commands[0].usage_count);
+    }
+
+    #[test]
+    fn merge_report_diffs_replaced_commands() {
+        let local = command("old description", "").with_command("echo a\necho b");
+        let incoming = command("new description", "").with_command("echo a\necho c");
+        let unchanged = command("", "").with_name("unchanged");
+        let mut trove = Trove::from_commands(&[local, unchanged.clone()]);
+        let report = trove.merge_trove_report(
+            &Trove::from_commands(&[incoming, unchanged]),
+            MergeStrategy::KeepIncoming,
+        );
+        assert!(report.changed);
+        assert_eq!(
+            vec![CommandDiff {
+                namespace: "test".to_string(),
+                name: "test".to_string(),
+                command: Some(" echo a\n-echo b\n+echo c\n".to_string()),
+                description: Some("-old description\n+new description\n".to_string()),
+            }],
+            report.diffs
+        );
+    }
+
+    #[test]
+    fn merge_report_strategies() {
+        let local = Trove::from_commands(&[
+            command("local", "").with_name("conflict"),
+            command("same", "").with_name("same"),
+        ]);
+        let other = Trove::from_commands(&[
+            command("incoming", "").with_name("conflict"),
+            command("same", "").with_name("same"),
+            command("new", "").with_name("new"),
+        ]);
+
+        let mut trove = local.clone();
+        let report = trove.merge_trove_report(&other, MergeStrategy::KeepLocal);
+        assert_eq!(vec!["test/new"], report.added);
+        assert_eq!(vec!["test/conflict", "test/same"], report.skipped);
+        assert_eq!("local", trove.commands[0].description);
+
+        let mut trove = local.clone();
+        let report = trove.merge_trove_report(&other, MergeStrategy::KeepIncoming);
+        assert_eq!(vec!["test/conflict"], report.overwritten);
+        assert_eq!("incoming", trove.commands[0].description);
+        assert_eq!(
+            "1 added, 1 overwritten, 0 renamed, 1 skipped, 0 removed",
+            report.summary()
+        );
+
+        let mut trove = local;
+        let report = trove.merge_trove_report(&other, MergeStrategy::KeepBoth);
+        assert_eq!(1, report.renamed.len());
+        let (name, new_name) = &report.renamed[0];
+        assert_eq!("test/conflict", name);
+        assert!(new_name.starts_with("test/conflict-"));
+        assert_eq!(4, trove.commands.len());
+        assert_eq!("local", trove.commands[0].description);
+    }
+
+    #[test]
+    fn merge_with_namespace_prefix() {
+        let git = |command: &str| {
+            HoardCmd::default()
+                .with_name("status")
+                .with_namespace("git")
+                .with_command(command)
+        };
+        let mut trove = Trove::from_commands(&[git("git status")]);
+        let shared = Trove::from_commands(&[git("git status -sb")]);
+
+        let report = trove.merge_trove_with_prefix(&shared, "shared/");
+        assert_eq!(vec!["shared/git/status"], report.added);
+        assert_eq!("git status", trove.commands[0].command);
+        assert!(trove.namespaces.contains("shared/git"));
+
+        // Importing the merged commands again does not prefix them twice
+        let reimported = Trove::from_commands(&[trove.commands[1].clone()]);
+        let report = trove.merge_trove_with_prefix(&reimported, "shared/");
+        assert_eq!(vec!["shared/git/status"], report.skipped);
+        assert_eq!(2, trove.commands.len());
+    }
+
+    #[test]
+    fn merges_do_not_apply_tombstones() {
+        let local = command("local", "").with_name("kept");
+        let mut tombstone = local.clone();
+        tombstone.mut_set_deleted(true);
+        let other = Trove::from_commands(&[tombstone]);
+
+        let mut trove = Trove::from_commands(std::slice::from_ref(&local));
+        trove.merge_trove(&other);
+        assert!(trove
+            .commands
+            .iter()
+            .any(|c| c.name == "kept" && !c.is_deleted));
+        let report = trove.merge_trove_report(&other, MergeStrategy::KeepIncoming);
+        assert!(report.removed.is_empty());
+        assert!(trove.get_command("kept").is_some());
+        let merged = Trove::from_commands(std::slice::from_ref(&local))
+            .merge_interactive(&other, |l, _| l.clone());
+        assert!(merged.commands.iter().any(|c| c.name == "kept"));
+
+        // Applying the tombstone as a patch removes the command and its now empty namespace
+        let mut trove = Trove::from_commands(&[local]);
+        assert!(trove.apply_patch(&other).changed);
+        assert!(trove.is_empty());
+        assert!(!trove.has_namespace("test"));
+    }
+}
@@ -0,0 +1,191 @@
+use std::fmt;
+
+use crate::core::error::TroveError;
+use crate::core::trove::{Trove, CARGO_VERSION};
+
+/// A `major.minor.patch` version of a trove collection
+///
+/// Pre-release and build suffixes like `-beta.1` or `+build` are ignored when comparing versions
+#[derive(Debug, Clone, Copy, PartialEq, Eq, PartialOrd, Ord)]
+pub struct TroveVersion {
+    pub major: u64,
+    pub minor: u64,
+    pub patch: u64,
+}
+
+impl TroveVersion {
+    /// Parse a version like `1.2.3`, missing minor and patch numbers default to `0`
+    ///
+    /// Returns `TroveError::InvalidVersion` if `version` is no semantic version
+    pub fn parse(version: &str) -> Result<Self, TroveError> {
+        let invalid = || TroveError::InvalidVersion(version.to_string());
+        let core = version
+            .trim()
+            .trim_start_matches('v')
+            .split(['-', '+'])
+            .next()
+            .unwrap_or_default();
+        let mut numbers = core.split('.').map(str::parse::<u64>);
+        let mut next = |required: bool| match numbers.next() {
+            Some(number) => number.map_err(|_| invalid()),
+            None if required => Err(invalid()),
+            None => Ok(0),
+        };
+        let parsed = Self {
+            major: next(true)?,
+            minor: next(false)?,
+            patch: next(false)?,
+        };
+        if numbers.next().is_some() {
+            return Err(invalid());
+        }
+        Ok(parsed)
+    }
+}
+
+impl fmt::Display for TroveVersion {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        write!(f, "{}.{}.{}", self.major, self.minor, self.patch)
+    }
+}
+
+/// A change of the trove format that has to be applied to collections stored with older hoard versions
+pub trait MigrationStep {
+    /// Short description of the change, used for logging
+    fn description(&self) -> &str;
+
+    /// Rewrite `trove` to the new format
+    fn migrate(&self, trove: &mut Trove) -> Result<(), TroveError>;
+}
+
+/// A registered `MigrationStep` together with the trove versions it applies to
+///
+/// The step runs for troves stored with a version in `from..until`
+pub struct Migration {
+    pub from: TroveVersion,
+    pub until: TroveVersion,
+    pub step: Box<dyn MigrationStep>,
+}
+
+impl Migration {
+    fn applies_to(&self, version: TroveVersion) -> bool {
+        self.from <= version && version < self.until
+    }
+}
+
+// Registered steps are boxed, which is not possible in a const fn
+#[allow(clippy::missing_const_for_fn)]
+/// All migration steps of the trove format, in the order they have to be applied
+///
+/// Register a step here when a breaking change is made to the trove format
+fn migrations() -> Vec<Migration> {
+    Vec::new()
+}
+
+impl Trove {
+    #[allow(dead_code)]
+    /// Migrate a trove collection stored with an older hoard version to the current format
+    ///
+    /// Applies all registered migration steps matching the stored `version` and bumps it to the running hoard version.
+    /// Returns whether any migration step ran. Troves stored with a newer hoard version are left unchanged
+    pub fn migrate(&mut self) -> Result<bool, TroveError> {
+        self.migrate_with(&migrations(), CARGO_VERSION)
+    }
+
+    fn migrate_with(
+        &mut self,
+        migrations: &[Migration],
+        current: &str,
+    ) -> Result<bool, TroveError> {
+        let current_version = TroveVersion::parse(current)?;
+        let stored = TroveVersion::parse(&self.version)?;
+        if stored >= current_version {
+            return Ok(false);
+        }
+        let mut migrated = false;
+        for migration in migrations.iter().filter(|m| m.applies_to(stored)) {
+            log::info!(
+                "Migrating trove from version {stored}: {}",
+                migration.step.description()
+            );
+            migration.step.migrate(self)?;
+            migrated = true;
+        }
+        self.version = current.to_string();
+        Ok(migrated)
+    }
+}
+
+#[cfg(test)]
+mod test_commands {
+    use super::*;
+    use crate::core::HoardCmd;
+
+    struct UppercaseNamespaces;
+
+    impl MigrationStep for UppercaseNamespaces {
+        fn description(&self) -> &'static str {
+            "uppercase namespaces"
+        }
+
+        fn migrate(&self, trove: &mut Trove) -> Result<(), TroveError> {
+            for command in &mut trove.commands {
+                command.namespace = command.namespace.to_uppercase();
+            }
+            Ok(())
+        }
+    }
+
+    fn trove(version: &str) -> Trove {
+        let mut trove = Trove::from_commands(&[HoardCmd::default()
+            .with_name("test")
+            .with_namespace("test")
+            .with_command("echo test")]);
+        trove.version = version.to_string();
+        trove
+    }
+
+    #[test]
+    fn parse_versions() {
+        let version = TroveVersion::parse("v1.2.3-beta.1").unwrap();
+        assert_eq!("1.2.3", version.to_string());
+        assert!(TroveVersion::parse("1").unwrap() < TroveVersion::parse("1.0.1").unwrap());
+        assert!(TroveVersion::parse("1.x").is_err());
+        assert!(TroveVersion::parse("1.2.3.4").is_err());
+        assert!(TroveVersion::parse("").is_err());
+    }
+
+    #[test]
+    fn migrate_applies_matching_steps() {
+        let migrations = [Migration {
+            from: TroveVersion::parse("1.0.0").unwrap(),
+            until: TroveVersion::parse("2.0.0").unwrap(),
+            step: Box::new(UppercaseNamespaces),
+        }];
+
+        let mut old = trove("1.4.0");
+        assert!(old.migrate_with(&migrations, "2.1.0").unwrap());
+        assert_eq!("TEST", old.commands[0].namespace);
+        assert_eq!("2.1.0", old.version);
+
+        let mut unaffected = trove("2.0.0");
+        assert!(!unaffected.migrate_with(&migrations, "2.1.0").unwrap());
+        assert_eq!("test", unaffected.commands[0].namespace);
+        assert_eq!("2.1.0", unaffected.version);
+
+        let mut newer = trove("3.0.0");
+        assert!(!newer.migrate_with(&migrations, "2.1.0").unwrap());
+        assert_eq!("3.0.0", newer.version);
+    }
+
+    #[test]
+    fn migrate_bumps_version() {
+        let mut trove = trove("0.1.0");
+        assert!(!trove.migrate().unwrap());
+        assert_eq!(CARGO_VERSION, trove.version);
+        assert!(matches!(
+            self::trove("latest").migrate(),
+            Err(TroveError::InvalidVersion(_))
+        ));
+    }
+}
@@ -1,7 +1,28 @@
+pub mod archive;
+pub mod details;
+pub mod diff;
+pub mod duplicates;
 pub mod error;
+pub mod events;
+pub mod exec;
+pub mod explain;
+pub mod export;
+pub mod favorites;
+pub mod index;
+pub mod lint;
+pub mod merge;
+pub mod migrate;
 pub mod parameters;
+pub mod patch;
+pub mod query;
+pub mod references;
+pub mod replace;
+pub mod search;
+pub mod tag_colors;
 pub mod trove;
+pub mod yaml;
 
+use crate::config::HoardConfig;
 use crate::core::error::HoardErr;
 use crate::core::trove::Trove;
 use crate::gui::merge::{with_conflict_resolve_prompt, ConflictResolve};
@@ -9,15 +30,62 @@ use crate::gui::prompts::{prompt_input, prompt_input_validate, prompt_select_wit
 use rand::distributions::Alphanumeric;
 use rand::Rng;
 use serde::{Deserialize, Serialize};
+use std::collections::HashMap;
+use std::fmt::Write;
+use std::path::PathBuf;
 use std::time;
 
+/// Length of random name suffixes resolving name collisions, if not configured otherwise
+pub const DEFAULT_SUFFIX_LENGTH: usize = 4;
+
+/// How the name suffix resolving a name collision is picked
+#[derive(Debug, Clone, Copy, Default, PartialEq, Eq, Serialize, Deserialize)]
+#[serde(rename_all = "snake_case")]
+pub enum SuffixStrategy {
+    /// Random characters, e.g. `deploy-9fk2`
+    #[default]
+    Random,
+    /// The lowest free number starting at 2, e.g. `deploy-2`
+    Counter,
+    /// The current date, e.g. `deploy-20240601`, followed by a number if that name is taken as well
+    Date,
+}
+
 fn default_time() -> time::SystemTime {
     time::SystemTime::now()
 }
 
+/// Generate a random (version 4) UUID to be used as a stable command id
+pub fn generate_id() -> String {
+    let mut bytes: [u8; 16] = rand::thread_rng().gen();
+    // Set version (4) and variant (RFC 4122) bits
+    bytes[6] = (bytes[6] & 0x0f) | 0x40;
+    bytes[8] = (bytes[8] & 0x3f) | 0x80;
+    let hex = bytes.iter().fold(String::new(), |mut hex, b| {
+        let _ = write!(hex, "{b:02x}");
+        hex
+    });
+    format!(
+        "{}-{}-{}-{}-{}",
+        &hex[0..8],
+        &hex[8..12],
+        &hex[12..16],
+        &hex[16..20],
+        &hex[20..32]
+    )
+}
+
+/// FNV-1a hash of `bytes`, stable across runs and platforms unlike the std hasher
+pub fn fnv1a(bytes: &[u8]) -> u64 {
+    bytes.iter().fold(0xcbf2_9ce4_8422_2325_u64, |hash, b| {
+        (hash ^ u64::from(*b)).wrapping_mul(0x0100_0000_01b3)
+    })
+}
+
 /// Storage for the saved command structure
 ///
 /// A `HoardCmd` can store the following parameters
+/// - `id`: A stable identifier of the command which persists across renames
 /// - `name`: The name of the command by which it is referenced
 /// - `command`: The terminal command to be stored and executed
 /// - `description`: A description of the command for the user
@@ -31,8 +99,19 @@ fn default_time() -> time::SystemTime {
 /// - `is_deleted`: A flag to indicate if the command is deleted
 /// - `namespace`: The namespace the command belongs to
 /// - `namespace_id`: The id of the namespace the command belongs to
+/// - `confirm`: A flag to indicate that running the command needs an explicit confirmation
+/// - `param_tokens`: Parameter start and ending token overriding the tokens of the config for this command
+/// - `order`: Position of the command within its namespace when listing, unordered commands are listed last
+/// - `parameter_defaults`: Default values of parameters by name, pre-filled when prompting for parameters
+/// - `source`: The trove file the command was loaded from, if loaded with `Trove::load_and_merge`
+#[allow(clippy::struct_excessive_bools)]
 #[derive(Debug, Clone, Serialize, Deserialize)]
 pub struct HoardCmd {
+    /// A stable identifier of the command which persists across renames
+    /// Commands loaded from older trove files get one assigned on load
+    #[serde(default = "generate_id")]
+    pub id: String,
+
     /// The name of the command by which it is referenced
     pub name: String,
 
@@ -75,6 +154,29 @@ pub struct HoardCmd {
 
     /// The namespace the command belongs to
     pub namespace: String,
+
+    /// A flag to indicate that running the command needs an explicit confirmation, e.g. for destructive commands
+    #[serde(default)]
+    pub confirm: bool,
+
+    /// Parameter start and ending token of this command, overriding the tokens of the config
+    /// Useful for commands whose body clashes with the configured tokens, e.g. `{{` and `}}`
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub param_tokens: Option<(String, String)>,
+
+    /// Position of the command within its namespace when listing, see `Trove::reorder_namespace`
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub order: Option<usize>,
+
+    /// Default values of parameters by name, see `Parameterized::parameter_values`
+    /// Take precedence over default values declared in the command string
+    #[serde(default, skip_serializing_if = "HashMap::is_empty")]
+    pub parameter_defaults: HashMap<String, String>,
+
+    /// The trove file the command was loaded from, so changes can be written back to the right file
+    /// Never stored in trove files
+    #[serde(skip)]
+    pub source: Option<PathBuf>,
 }
 
 impl PartialEq for HoardCmd {
@@ -91,6 +193,7 @@ impl HoardCmd {
     /// Create a new `HoardCmd` with default values
     pub fn default() -> Self {
         Self {
+            id: generate_id(),
             name: String::new(),
             command: String::new(),
             description: String::new(),
@@ -103,6 +206,19 @@ impl HoardCmd {
             is_hidden: false,
             is_deleted: false,
             namespace: String::new(),
+            confirm: false,
+            param_tokens: None,
+            order: None,
+            parameter_defaults: HashMap::new(),
+            source: None,
+        }
+    }
+
+    /// set the id of the command
+    pub fn with_id(self, id: &str) -> Self {
+        Self {
+            id: id.to_string(),
+            ..self
         }
     }
 
@@ -154,6 +270,7 @@ impl HoardCmd {
     /// - A command that is not empty
     /// - A namespace that is not empty
     /// - `created/modified/last_used` that is not the `UNIX_EPOCH`
+    /// - valid `param_tokens`, if they are set
     pub fn is_valid(&self) -> bool {
         !self.name.is_empty()
             && !self.command.is_empty()
@@ -161,6 +278,119 @@ impl HoardCmd {
             && self.created != time::UNIX_EPOCH
             && self.modified != time::UNIX_EPOCH
             && self.last_used != time::UNIX_EPOCH
+            && self
+                .param_tokens
+                .as_ref()
+                .is_none_or(|(token, ending)| Self::are_param_tokens_valid(token, ending).is_ok())
+    }
+
+    /// Describe why the command is not valid, e.g. `name is empty, command is empty`, see `is_valid`
+    pub fn invalid_reason(&self) -> String {
+        let mut problems = Vec::new();
+        if self.name.is_empty() {
+            problems.push("name is empty".to_string());
+        }
+        if self.command.is_empty() {
+            problems.push("command is empty".to_string());
+        }
+        if self.namespace.is_empty() {
+            problems.push("namespace is empty".to_string());
+        }
+        for (field, timestamp) in [
+            ("created", self.created),
+            ("modified", self.modified),
+            ("last_used", self.last_used),
+        ] {
+            if timestamp == time::UNIX_EPOCH {
+                problems.push(format!("{field} is not set"));
+            }
+        }
+        if let Some(Err(e)) = self
+            .param_tokens
+            .as_ref()
+            .map(|(token, ending)| Self::are_param_tokens_valid(token, ending))
+        {
+            problems.push(format!("invalid parameter tokens: {e}"));
+        }
+        if problems.is_empty() {
+            "command is invalid".to_string()
+        } else {
+            problems.join(", ")
+        }
+    }
+
+    /// The command string with formatting differences removed, to compare commands by content
+    ///
+    /// Leading and trailing whitespace is trimmed and every other run of whitespace becomes a single space,
+    /// or a single newline if it contains a newline, as lines are separate commands.
+    /// Whitespace inside single or double quotes and escaped whitespace is kept as is,
+    /// so commands that only differ in their formatting normalize to the same string
+    pub fn normalized_command(&self) -> String {
+        let mut normalized = String::with_capacity(self.command.len());
+        let mut quote: Option<char> = None;
+        let mut escaped = false;
+        let mut pending_space: Option<char> = None;
+        for c in self.command.trim().chars() {
+            if quote.is_none() && !escaped && c.is_whitespace() {
+                if c == '\n' || pending_space.is_none() {
+                    pending_space = Some(if c == '\n' { '\n' } else { ' ' });
+                }
+                continue;
+            }
+            if let Some(space) = pending_space.take() {
+                normalized.push(space);
+            }
+            normalized.push(c);
+            match (quote, c) {
+                _ if escaped => escaped = false,
+                (Some('\''), '\'') | (Some('"'), '"') => quote = None,
+                (None | Some('"'), '\\') => escaped = true,
+                (None, '"' | '\'') => quote = Some(c),
+                _ => {}
+            }
+        }
+        normalized
+    }
+
+    #[allow(dead_code)]
+    /// A hex content id of the command, hashed from its namespace and its `normalized_command`
+    ///
+    /// Commands in the same namespace that only differ in name, metadata or formatting share the same content id
+    pub fn content_id(&self) -> String {
+        let content = format!("{}\0{}", self.namespace, self.normalized_command());
+        format!("{:016x}", fnv1a(content.as_bytes()))
+    }
+
+    #[allow(dead_code)]
+    /// set the parameter tokens of the command, overriding the tokens of the config
+    pub fn with_param_tokens(self, token: &str, ending_token: &str) -> Self {
+        Self {
+            param_tokens: Some((token.to_string(), ending_token.to_string())),
+            ..self
+        }
+    }
+
+    /// Get the parameter start and ending token of the command
+    /// The tokens of the command take precedence over the tokens of the config
+    pub fn parameter_tokens(&self, config: &HoardConfig) -> (String, String) {
+        self.param_tokens
+            .clone()
+            .unwrap_or_else(|| config.parameter_tokens())
+    }
+
+    /// Check if parameter tokens are valid
+    /// Both tokens can't be empty and have to differ from each other
+    /// Returns a Result with the error if the tokens are invalid
+    pub fn are_param_tokens_valid(token: &str, ending_token: &str) -> Result<(), HoardErr> {
+        if token.is_empty() || ending_token.is_empty() {
+            return Err(HoardErr::new("Parameter tokens can't be empty"));
+        }
+        if token == ending_token {
+            return Err(HoardErr::new(
+                "Parameter token can't be equal to the ending token",
+            ));
+        }
+        Ok(())
     }
 
     /// Check if a name is valid for saving
@@ -186,8 +416,25 @@ impl HoardCmd {
         Ok(())
     }
 
+    /// Returns the tags of the command without duplicates, in the order they were first seen
+    pub fn tags(&self) -> Vec<&str> {
+        let mut tags: Vec<&str> = Vec::new();
+        for tag in &self.tags {
+            if !tags.contains(&tag.as_str()) {
+                tags.push(tag);
+            }
+        }
+        tags
+    }
+
+    /// Remove duplicate tags of the command, keeping the first occurrence
+    pub fn mut_dedupe_tags(&mut self) -> &mut Self {
+        self.tags = self.tags().into_iter().map(str::to_string).collect();
+        self
+    }
+
     /// Return vector of tags as a string
-    /// Tags are separated by a comma
+    /// Tags are separated by a comma, duplicates are left out
     /// # Example  
     /// ```
     /// use hoardlib::command::HoardCmd;
@@ -200,13 +447,7 @@ impl HoardCmd {
     /// assert_eq!(cmd.get_tags_as_string(), "tag1,tag2,tag3");
     /// ```
     pub fn get_tags_as_string(&self) -> String {
-        let mut tags = String::new();
-        for tag in &self.tags {
-            tags.push_str(tag);
-            tags.push(',');
-        }
-        tags.pop();
-        tags
+        self.tags().join(",")
     }
 
     #[allow(dead_code)]
@@ -264,14 +505,28 @@ impl HoardCmd {
         }
     }
 
+    #[allow(dead_code)]
     /// set a random suffix to the name of the command
     pub fn with_random_name_suffix(self) -> Self {
-        let rng = rand::thread_rng();
-        let random_string: String = rng
-            .sample_iter(&Alphanumeric)
-            .take(4)
-            .map(char::from)
-            .collect();
+        self.with_random_name_suffix_from(DEFAULT_SUFFIX_LENGTH, None)
+    }
+
+    /// set a random suffix of `length` characters picked from `charset` to the name of the command
+    /// Alphanumeric characters are picked if `charset` is `None` or empty
+    pub fn with_random_name_suffix_from(self, length: usize, charset: Option<&str>) -> Self {
+        let mut rng = rand::thread_rng();
+        let charset: Vec<char> = charset.unwrap_or_default().chars().collect();
+        let random_string: String = if charset.is_empty() {
+            (&mut rng)
+                .sample_iter(&Alphanumeric)
+                .take(length)
+                .map(char::from)
+                .collect()
+        } else {
+            (0..length)
+                .map(|_| charset[rng.gen_range(0..charset.len())])
+                .collect()
+        };
         Self {
             name: format!("{}-{random_string}", self.name),
             ..self
@@ -284,10 +539,12 @@ impl HoardCmd {
         if tags.trim().is_empty() {
             return self;
         }
-        Self {
+        let mut command = Self {
             tags: tags.split(',').map(|s| s.trim().to_string()).collect(),
             ..self
-        }
+        };
+        command.mut_dedupe_tags();
+        command
     }
 
     /// Prompts the user for tags, with an optional default value, and validates the input.
@@ -583,10 +840,96 @@ mod test_commands {
         assert_eq!(expected, command.tags);
     }
 
+    #[test]
+    fn generated_ids_are_unique() {
+        let command1 = HoardCmd::default();
+        let command2 = HoardCmd::default();
+        assert_eq!(36, command1.id.len());
+        assert_ne!(command1.id, command2.id);
+    }
+
+    #[test]
+    fn id_survives_rename() {
+        let command = HoardCmd::default().with_name("foo");
+        let id = command.id.clone();
+        let renamed = command.with_name("bar").with_random_name_suffix();
+        assert_eq!(id, renamed.id);
+    }
+
     #[test]
     fn parse_string_with_only_whitespaces() {
         let command = HoardCmd::default().with_tags_raw("   ");
         let expected: Vec<String> = Vec::new();
         assert_eq!(expected, command.tags);
     }
+
+    #[test]
+    fn duplicate_tags_are_removed() {
+        let command = HoardCmd::default().with_tags_raw("foo,bar,foo, bar,baz");
+        assert_eq!(vec!["foo", "bar", "baz"], command.tags);
+
+        let mut command =
+            HoardCmd::default().with_tags(vec!["b".to_string(), "a".to_string(), "b".to_string()]);
+        assert_eq!(vec!["b", "a"], command.tags());
+        assert_eq!("b,a", command.get_tags_as_string());
+        command.mut_dedupe_tags();
+        assert_eq!(vec!["b", "a"], command.tags);
+    }
+
+    #[test]
+    fn param_tokens_override_config() {
+        let config = HoardConfig::default();
+        let command = HoardCmd::default()
+            .with_name("test")
+            .with_namespace("test")
+            .with_command("echo {{name}}");
+        assert_eq!(
+            ("#".to_string(), "!".to_string()),
+            command.parameter_tokens(&config)
+        );
+        let command = command.with_param_tokens("{{", "}}");
+        assert_eq!(
+            ("{{".to_string(), "}}".to_string()),
+            command.parameter_tokens(&config)
+        );
+        assert!(command.is_valid());
+
+        assert!(!command.clone().with_param_tokens("%", "%").is_valid());
+        assert!(!command.with_param_tokens("", "}}").is_valid());
+    }
+
+    #[test]
+    fn normalized_command() {
+        let command = |c: &str| HoardCmd::default().with_command(c).normalized_command();
+        assert_eq!("git commit -m", command("  git   commit\t-m \n"));
+        assert_eq!("echo 'a  b' \"c  d\"", command("echo   'a  b'  \"c  d\""));
+        assert_eq!("echo \"a \\\"  b\"", command("echo \"a \\\"  b\""));
+        assert_eq!("ls a\\  b", command("ls a\\  b"));
+        // Lines are separate commands
+        assert_eq!("echo a\necho b", command("echo a  \n\n  echo   b"));
+        assert_ne!(command("echo a\necho b"), command("echo a echo b"));
+        let spaced = HoardCmd::default()
+            .with_namespace("test")
+            .with_command("ls  -la");
+        let other = HoardCmd::default()
+            .with_name("other")
+            .with_namespace("test")
+            .with_command("ls -la");
+        assert_eq!(spaced.content_id(), other.content_id());
+        assert_ne!(
+            spaced.content_id(),
+            other.with_namespace("other").content_id()
+        );
+    }
+
+    #[test]
+    fn random_name_suffix_from_charset() {
+        let command = HoardCmd::default().with_name("foo");
+        let suffixed = command.clone().with_random_name_suffix_from(6, Some("ab"));
+        let suffix = suffixed.name.strip_prefix("foo-").unwrap();
+        assert_eq!(6, suffix.len());
+        assert!(suffix.chars().all(|c| c == 'a' || c == 'b'));
+        let suffixed = command.with_random_name_suffix_from(3, Some(""));
+        assert!(suffixed.name[4..].chars().all(char::is_alphanumeric));
+    }
 }
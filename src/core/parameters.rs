@@ -1,8 +1,152 @@
 use regex::Regex;
+use std::collections::HashMap;
 
+use crate::core::error::TroveError;
 use crate::core::HoardCmd;
 use crate::gui::prompts::prompt_input;
 
+#[allow(dead_code)]
+/// Source of completions a prompt can offer for a parameter
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub enum CompletionSource {
+    /// Complete from file paths, declared as `:files`
+    Files,
+    /// Complete from directory paths, declared as `:dirs`
+    Dirs,
+    /// Complete from a static list of values, declared as `:list(a,b,c)`
+    List(Vec<String>),
+    /// Complete from the output lines of a shell command, declared as `:cmd(git branch)`
+    Command(String),
+}
+
+impl CompletionSource {
+    /// Parse a completion declaration off the start of `spec`, returning it and the rest of `spec`
+    fn parse(spec: &str) -> Option<(Self, &str)> {
+        let parenthesized = |prefix: &str| {
+            let inner = spec.strip_prefix(prefix)?;
+            let end = inner.find(')')?;
+            Some((&inner[..end], &inner[end + 1..]))
+        };
+        if let Some((values, rest)) = parenthesized("list(") {
+            let values = values.split(',').map(|v| v.trim().to_string()).collect();
+            return Some((Self::List(values), rest));
+        }
+        if let Some((command, rest)) = parenthesized("cmd(") {
+            return Some((Self::Command(command.to_string()), rest));
+        }
+        let end = spec.find('=').unwrap_or(spec.len());
+        let source = match &spec[..end] {
+            "files" => Self::Files,
+            "dirs" => Self::Dirs,
+            _ => return None,
+        };
+        Some((source, &spec[end..]))
+    }
+}
+
+#[allow(dead_code)]
+/// A parameter found in a command string
+///
+/// Parameters are written as `<token><name><ending_token>`, e.g. `#host!`.
+/// A completion source can be declared after the name with `:`, e.g. `#config:files!`, see `CompletionSource`.
+/// A default value can be declared last with `=`, e.g. `#port=8080!` or `#env:list(dev,prod)=dev!`
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct ParameterSpec {
+    /// The name of the parameter
+    pub name: String,
+    /// The declared default value of the parameter
+    pub default: Option<String>,
+    /// The declared source of completions for the parameter
+    pub completion: Option<CompletionSource>,
+    /// The full parameter as written in the command string, including the tokens
+    pub raw: String,
+}
+
+impl ParameterSpec {
+    fn parse(raw: &str, body: &str) -> Self {
+        let name_end = body.find([':', '=']).unwrap_or(body.len());
+        let (name, mut rest) = body.split_at(name_end);
+        let mut completion = None;
+        if let Some(spec) = rest.strip_prefix(':') {
+            // An unknown completion source is kept as part of the name
+            match CompletionSource::parse(spec) {
+                Some((source, after)) => {
+                    completion = Some(source);
+                    rest = after;
+                }
+                None => {
+                    return Self::parse_name_and_default(raw, body);
+                }
+            }
+        }
+        Self {
+            name: name.to_string(),
+            default: rest.strip_prefix('=').map(ToString::to_string),
+            completion,
+            raw: raw.to_string(),
+        }
+    }
+
+    fn parse_name_and_default(raw: &str, body: &str) -> Self {
+        let (name, default) = match body.split_once('=') {
+            Some((name, default)) => (name, Some(default.to_string())),
+            None => (body, None),
+        };
+        Self {
+            name: name.to_string(),
+            default,
+            completion: None,
+            raw: raw.to_string(),
+        }
+    }
+}
+
+/// Scan `command` for parameters in order of appearance
+///
+/// Returns the parameters found and the byte position of the first token without an ending token, after which scanning stops
+fn scan_parameters(
+    command: &str,
+    token: &str,
+    ending_token: &str,
+) -> (Vec<ParameterSpec>, Option<usize>) {
+    let mut specs = Vec::new();
+    if token.is_empty() {
+        return (specs, None);
+    }
+    let mut offset = 0;
+    while let Some(start) = command[offset..].find(token).map(|i| i + offset) {
+        let after_token = &command[start + token.len()..];
+        let body_end = if ending_token.is_empty() {
+            Some(
+                after_token
+                    .find(char::is_whitespace)
+                    .unwrap_or(after_token.len()),
+            )
+        } else {
+            after_token.find(ending_token)
+        };
+        let Some(body_end) = body_end else {
+            return (specs, Some(start));
+        };
+        let end = start + token.len() + body_end + ending_token.len();
+        specs.push(ParameterSpec::parse(
+            &command[start..end],
+            &after_token[..body_end],
+        ));
+        offset = end;
+    }
+    (specs, None)
+}
+
+#[allow(dead_code)]
+/// Find all parameters in `command` in order of appearance
+///
+/// If `ending_token` is empty, a parameter name ends at the next whitespace
+/// Parameters without an ending token are skipped
+pub fn parameter_specs(command: &str, token: &str, ending_token: &str) -> Vec<ParameterSpec> {
+    scan_parameters(command, token, ending_token).0
+}
+
 pub trait Parameterized {
     /// Checks if the command string contains a specific token.
     ///
@@ -134,6 +278,122 @@ pub trait Parameterized {
     /// // The command string is updated with the user's input.
     /// ```
     fn with_input_parameters(&mut self, token: &str, ending_token: &str) -> HoardCmd;
+
+    /// Lists all parameters of the command string in order of appearance.
+    ///
+    /// # Arguments
+    ///
+    /// * `token` - A string slice that holds the token of the parameter.
+    /// * `ending_token` - A string slice that holds the ending token of the parameter.
+    ///
+    /// # Returns
+    ///
+    /// This function returns a `ParameterSpec` with name, optional default value and optional completion source for each parameter.
+    ///
+    /// # Example
+    ///
+    /// ```
+    /// let command = HoardCmd::default().with_command("curl #host!:#port=8080!");
+    /// let specs = command.parameter_specs("#", "!");
+    /// assert_eq!(specs[1].default, Some("8080".to_string()));
+    /// ```
+    #[allow(dead_code)]
+    fn parameter_specs(&self, token: &str, ending_token: &str) -> Vec<ParameterSpec>;
+
+    /// Replaces every parameter with a declared default value by its default.
+    ///
+    /// Parameters without a default value are left untouched, so the resulting command can still be filled out.
+    ///
+    /// # Arguments
+    ///
+    /// * `token` - A string slice that holds the token of the parameter.
+    /// * `ending_token` - A string slice that holds the ending token of the parameter.
+    ///
+    /// # Returns
+    ///
+    /// This function returns a new instance of the command with the defaults applied.
+    ///
+    /// # Example
+    ///
+    /// ```
+    /// let command = HoardCmd::default().with_command("curl #host!:#port=8080!");
+    /// let applied = command.with_defaults_applied("#", "!");
+    /// assert_eq!(applied.command, "curl #host!:8080");
+    /// ```
+    #[allow(dead_code)]
+    fn with_defaults_applied(&self, token: &str, ending_token: &str) -> HoardCmd;
+
+    /// Lists the names of all parameters of the command string in order of first appearance.
+    ///
+    /// Parameters used more than once are listed once.
+    ///
+    /// # Arguments
+    ///
+    /// * `token` - A string slice that holds the token of the parameter.
+    /// * `ending_token` - A string slice that holds the ending token of the parameter.
+    ///
+    /// # Returns
+    ///
+    /// This function returns the parameter names, or `TroveError::UnterminatedParameter` if a token has no ending token.
+    ///
+    /// # Example
+    ///
+    /// ```
+    /// let command = HoardCmd::default().with_command("ssh #user!@#host! -- ping #host!");
+    /// assert_eq!(command.parameters("#", "!").unwrap(), vec!["user", "host"]);
+    /// ```
+    #[allow(dead_code)]
+    fn parameters(&self, token: &str, ending_token: &str) -> Result<Vec<String>, TroveError>;
+
+    /// Merges user supplied parameter values on top of the stored parameter defaults.
+    ///
+    /// # Arguments
+    ///
+    /// * `overrides` - A map of parameter names to the values supplied by the user.
+    ///
+    /// # Returns
+    ///
+    /// This function returns the `parameter_defaults` of the command with every value of `overrides` replacing its default.
+    ///
+    /// # Example
+    ///
+    /// ```
+    /// let mut command = HoardCmd::default().with_command("curl #host!:#port!");
+    /// command.parameter_defaults.insert("host".to_string(), "localhost".to_string());
+    /// let values = command.parameter_values(&HashMap::from([("port".to_string(), "80".to_string())]));
+    /// assert_eq!(values["host"], "localhost");
+    /// ```
+    #[allow(dead_code)]
+    fn parameter_values(&self, overrides: &HashMap<String, String>) -> HashMap<String, String>;
+
+    /// Fills out all parameters of the command string without prompting.
+    ///
+    /// Each parameter takes its value from `values` by name, falling back to its declared default value.
+    ///
+    /// # Arguments
+    ///
+    /// * `token` - A string slice that holds the token of the parameter.
+    /// * `ending_token` - A string slice that holds the ending token of the parameter.
+    /// * `values` - A map of parameter names to the values to fill in.
+    ///
+    /// # Returns
+    ///
+    /// This function returns the resolved command string, or `TroveError::MissingParameters` listing every parameter without a value.
+    ///
+    /// # Example
+    ///
+    /// ```
+    /// let command = HoardCmd::default().with_command("curl #host!:#port=8080!");
+    /// let values = HashMap::from([("host".to_string(), "localhost".to_string())]);
+    /// assert_eq!(command.resolve_parameters("#", "!", &values).unwrap(), "curl localhost:8080");
+    /// ```
+    #[allow(dead_code)]
+    fn resolve_parameters(
+        &self,
+        token: &str,
+        ending_token: &str,
+        values: &HashMap<String, String>,
+    ) -> Result<String, TroveError>;
 }
 
 impl Parameterized for HoardCmd {
@@ -181,7 +441,18 @@ impl Parameterized for HoardCmd {
                 (param_count + 1),
                 self.command
             );
-            let parameter = prompt_input(&prompt_dialog, false, None);
+            // An empty input takes the default value of the parameter
+            let default = scan_parameters(&self.command, token, ending_token)
+                .0
+                .into_iter()
+                .next()
+                .and_then(|spec| {
+                    self.parameter_defaults
+                        .get(&spec.name)
+                        .cloned()
+                        .or(spec.default)
+                });
+            let parameter = prompt_input(&prompt_dialog, false, default);
             self.command = self
                 .replace_parameter(token, ending_token, &parameter)
                 .command;
@@ -189,6 +460,76 @@ impl Parameterized for HoardCmd {
         }
         self.clone()
     }
+
+    fn parameter_specs(&self, token: &str, ending_token: &str) -> Vec<ParameterSpec> {
+        parameter_specs(&self.command, token, ending_token)
+    }
+
+    fn parameters(&self, token: &str, ending_token: &str) -> Result<Vec<String>, TroveError> {
+        let (specs, unterminated) = scan_parameters(&self.command, token, ending_token);
+        if let Some(position) = unterminated {
+            return Err(TroveError::UnterminatedParameter {
+                command: self.command.clone(),
+                position,
+                ending_token: ending_token.to_string(),
+            });
+        }
+        let mut names: Vec<String> = Vec::new();
+        for spec in specs {
+            if !names.contains(&spec.name) {
+                names.push(spec.name);
+            }
+        }
+        Ok(names)
+    }
+
+    fn parameter_values(&self, overrides: &HashMap<String, String>) -> HashMap<String, String> {
+        let mut values = self.parameter_defaults.clone();
+        values.extend(overrides.iter().map(|(k, v)| (k.clone(), v.clone())));
+        values
+    }
+
+    fn with_defaults_applied(&self, token: &str, ending_token: &str) -> Self {
+        let mut command = self.command.clone();
+        for spec in self.parameter_specs(token, ending_token) {
+            if let Some(default) = &spec.default {
+                command = command.replacen(&spec.raw, default, 1);
+            }
+        }
+        Self {
+            command,
+            ..self.clone()
+        }
+    }
+
+    fn resolve_parameters(
+        &self,
+        token: &str,
+        ending_token: &str,
+        values: &HashMap<String, String>,
+    ) -> Result<String, TroveError> {
+        let mut command = self.command.clone();
+        let mut missing: Vec<String> = Vec::new();
+        for spec in self.parameter_specs(token, ending_token) {
+            match values
+                .get(&spec.name)
+                .or_else(|| self.parameter_defaults.get(&spec.name))
+                .or(spec.default.as_ref())
+            {
+                Some(value) => command = command.replacen(&spec.raw, value, 1),
+                None if !missing.contains(&spec.name) => missing.push(spec.name),
+                None => {}
+            }
+        }
+        if missing.is_empty() {
+            Ok(command)
+        } else {
+            Err(TroveError::MissingParameters {
+                command: self.name.clone(),
+                parameters: missing,
+            })
+        }
+    }
 }
 
 #[cfg(test)]
@@ -268,4 +609,143 @@ mod test_commands {
         let expected = HoardCmd::default().with_command("test1replacementtest3");
         assert_eq!(expected, command.replace_parameter("#", "!", "replacement"));
     }
+
+    #[test]
+    fn test_parameter_specs() {
+        let command = HoardCmd::default().with_command("curl #host!:#port=8080! #path=/a=b!");
+        let specs = command.parameter_specs("#", "!");
+        assert_eq!(3, specs.len());
+        assert_eq!("host", specs[0].name);
+        assert_eq!(None, specs[0].default);
+        assert_eq!("#host!", specs[0].raw);
+        assert_eq!("port", specs[1].name);
+        assert_eq!(Some("8080".to_string()), specs[1].default);
+        assert_eq!("path", specs[2].name);
+        assert_eq!(Some("/a=b".to_string()), specs[2].default);
+    }
+
+    #[test]
+    fn test_parameter_specs_without_ending_token() {
+        let command = HoardCmd::default().with_command("ssh #host -p #port=22");
+        let specs = command.parameter_specs("#", "");
+        assert_eq!(
+            vec!["#host", "#port=22"],
+            specs.iter().map(|s| s.raw.as_str()).collect::<Vec<_>>()
+        );
+    }
+
+    #[test]
+    fn test_with_defaults_applied() {
+        let command = HoardCmd::default()
+            .with_name("curl")
+            .with_command("curl #host!:#port=8080!/#port=8080!");
+        let applied = command.with_defaults_applied("#", "!");
+        assert_eq!("curl #host!:8080/8080", applied.command);
+        assert_eq!("curl", applied.name);
+    }
+
+    #[test]
+    fn test_resolve_parameters() {
+        let command = HoardCmd::default()
+            .with_name("curl")
+            .with_command("curl #host!:#port=8080!/#path!");
+        let mut values = HashMap::new();
+        values.insert("host".to_string(), "localhost".to_string());
+        match command.resolve_parameters("#", "!", &values) {
+            Err(TroveError::MissingParameters {
+                command,
+                parameters,
+            }) => {
+                assert_eq!("curl", command);
+                assert_eq!(vec!["path"], parameters);
+            }
+            _ => panic!("expected missing parameters"),
+        }
+
+        values.insert("path".to_string(), "index.html".to_string());
+        values.insert("port".to_string(), "80".to_string());
+        assert_eq!(
+            "curl localhost:80/index.html",
+            command.resolve_parameters("#", "!", &values).unwrap()
+        );
+    }
+
+    #[test]
+    fn test_parameter_specs_with_completion() {
+        let command = HoardCmd::default().with_command(
+            "deploy #env:list(dev, prod)=dev! #config:files! #branch:cmd(git branch)! #url=http://x:80! #a:b!",
+        );
+        let specs = command.parameter_specs("#", "!");
+        assert_eq!("env", specs[0].name);
+        assert_eq!(
+            Some(CompletionSource::List(vec![
+                "dev".to_string(),
+                "prod".to_string()
+            ])),
+            specs[0].completion
+        );
+        assert_eq!(Some("dev".to_string()), specs[0].default);
+        assert_eq!(Some(CompletionSource::Files), specs[1].completion);
+        assert_eq!(None, specs[1].default);
+        assert_eq!(
+            Some(CompletionSource::Command("git branch".to_string())),
+            specs[2].completion
+        );
+        assert_eq!("url", specs[3].name);
+        assert_eq!(Some("http://x:80".to_string()), specs[3].default);
+        assert_eq!(None, specs[3].completion);
+        // Unknown completion sources stay part of the name
+        assert_eq!("a:b", specs[4].name);
+        assert_eq!(None, specs[4].completion);
+    }
+
+    #[test]
+    fn test_parameters() {
+        let command =
+            HoardCmd::default().with_command("ssh #user!@#host=localhost! -- ping #host!");
+        assert_eq!(vec!["user", "host"], command.parameters("#", "!").unwrap());
+        assert!(HoardCmd::default()
+            .with_command("echo")
+            .parameters("#", "!")
+            .unwrap()
+            .is_empty());
+
+        let unterminated = HoardCmd::default().with_command("echo #a! #b");
+        match unterminated.parameters("#", "!") {
+            Err(TroveError::UnterminatedParameter { position, .. }) => assert_eq!(9, position),
+            other => panic!("expected an unterminated parameter, got {other:?}"),
+        }
+    }
+
+    #[test]
+    fn test_parameter_defaults() {
+        let mut command = HoardCmd::default().with_command("curl #host!:#port=8080!/#path!");
+        command
+            .parameter_defaults
+            .insert("host".to_string(), "localhost".to_string());
+        command
+            .parameter_defaults
+            .insert("port".to_string(), "80".to_string());
+
+        let overrides = HashMap::from([("host".to_string(), "example.com".to_string())]);
+        let values = command.parameter_values(&overrides);
+        assert_eq!("example.com", values["host"]);
+        assert_eq!("80", values["port"]);
+
+        let values = HashMap::from([("path".to_string(), "index".to_string())]);
+        assert_eq!(
+            "curl localhost:80/index",
+            command.resolve_parameters("#", "!", &values).unwrap()
+        );
+    }
+
+    #[test]
+    fn test_parameter_defaults_are_optional_in_yaml() {
+        let yaml = "name: test\nnamespace: test\ncommand: echo #a!\ndescription: ''\ntags: []\n";
+        let command: HoardCmd = serde_yaml::from_str(yaml).unwrap();
+        assert!(command.parameter_defaults.is_empty());
+        assert!(!serde_yaml::to_string(&command)
+            .unwrap()
+            .contains("parameter_defaults"));
+    }
 }
@@ -0,0 +1,154 @@
+use std::time::SystemTime;
+
+use crate::core::error::TroveError;
+use crate::core::events::TroveEvent;
+use crate::core::trove::Trove;
+use crate::core::HoardCmd;
+
+/// Changes to apply to single fields of a command with `Trove::patch_command`
+///
+/// Only fields set to `Some` are changed. `tags` replaces all tags of the command
+#[allow(dead_code)]
+#[derive(Debug, Clone, Default, PartialEq, Eq)]
+pub struct HoardCmdPatch {
+    pub name: Option<String>,
+    pub namespace: Option<String>,
+    pub command: Option<String>,
+    pub description: Option<String>,
+    pub tags: Option<Vec<String>>,
+}
+
+impl HoardCmdPatch {
+    /// Apply the set fields of the patch to a copy of `command`
+    fn apply(self, command: &HoardCmd) -> HoardCmd {
+        let mut patched = command.clone();
+        if let Some(name) = self.name {
+            patched.name = name;
+        }
+        if let Some(namespace) = self.namespace {
+            patched.namespace = namespace;
+        }
+        if let Some(command) = self.command {
+            patched.command = command;
+        }
+        if let Some(description) = self.description {
+            patched.description = description;
+        }
+        if let Some(tags) = self.tags {
+            patched.tags = tags;
+        }
+        patched
+    }
+}
+
+impl Trove {
+    #[allow(dead_code)]
+    /// Change single fields of the command `name`, keeping all other fields
+    ///
+    /// `name` may be qualified with a namespace like `namespace/name`.
+    /// Returns `TroveError::CommandNotFound` or `TroveError::AmbiguousCommand` if `name` does not identify a single command,
+    /// `TroveError::Invalid` if the patched command is not valid and `TroveError::CommandCollision`
+    /// if the new name or namespace is taken. The trove is not changed on error
+    pub fn patch_command(&mut self, name: &str, patch: HoardCmdPatch) -> Result<(), TroveError> {
+        let position = self.command_position(name)?;
+        let original = &self.commands[position];
+        let mut patched = patch.apply(original);
+        if !patched.is_valid() {
+            return Err(TroveError::Invalid(format!(
+                "{}/{}: {}",
+                patched.namespace,
+                patched.name,
+                patched.invalid_reason()
+            )));
+        }
+        if let Some(existing) = self.get_command_collision_except(&patched, position) {
+            return Err(TroveError::CommandCollision {
+                namespace: existing.namespace.clone(),
+                name: existing.name.clone(),
+                command: existing.command.clone(),
+            });
+        }
+        patched.mut_dedupe_tags();
+        patched.modified = SystemTime::now();
+        self.emit(|| TroveEvent::Updated {
+            namespace: original.namespace.clone(),
+            name: original.name.clone(),
+            command: patched.clone(),
+        });
+        let moved = original.namespace != patched.namespace;
+        self.commands[position] = patched;
+        if moved {
+            self.rebuild_namespaces();
+        }
+        Ok(())
+    }
+}
+
+#[cfg(test)]
+mod test_commands {
+    use super::*;
+
+    fn trove() -> Trove {
+        let command = |name: &str, namespace: &str, command: &str| {
+            HoardCmd::default()
+                .with_name(name)
+                .with_namespace(namespace)
+                .with_command(command)
+        };
+        Trove::from_commands(&[
+            command("status", "git", "git status").with_description("Show the status"),
+            command("log", "git", "git log"),
+            command("log", "svn", "svn log"),
+        ])
+    }
+
+    #[test]
+    fn patch_single_fields() {
+        let mut trove = trove();
+        let patch = HoardCmdPatch {
+            description: Some("Show the working tree status".to_string()),
+            ..HoardCmdPatch::default()
+        };
+        trove.patch_command("status", patch).unwrap();
+        let status = trove.get_command("git/status").unwrap();
+        assert_eq!("Show the working tree status", status.description);
+        assert_eq!("git status", status.command);
+
+        let patch = HoardCmdPatch {
+            name: Some("history".to_string()),
+            namespace: Some("vcs".to_string()),
+            ..HoardCmdPatch::default()
+        };
+        trove.patch_command("svn/log", patch).unwrap();
+        assert_eq!("svn log", trove.get_command("vcs/history").unwrap().command);
+        assert!(!trove.has_namespace("svn"));
+    }
+
+    #[test]
+    fn patch_errors() {
+        let mut trove = trove();
+        let rename = |name: &str| HoardCmdPatch {
+            name: Some(name.to_string()),
+            ..HoardCmdPatch::default()
+        };
+        assert!(matches!(
+            trove.patch_command("missing", rename("x")),
+            Err(TroveError::CommandNotFound(_))
+        ));
+        assert!(matches!(
+            trove.patch_command("log", rename("x")),
+            Err(TroveError::AmbiguousCommand { .. })
+        ));
+        assert!(matches!(
+            trove.patch_command("status", rename("log")),
+            Err(TroveError::CommandCollision { .. })
+        ));
+        assert!(matches!(
+            trove.patch_command("status", rename("")),
+            Err(TroveError::Invalid(message)) if message == "git/: name is empty"
+        ));
+        assert_eq!("status", trove.commands[0].name);
+        // Keeping the name is no collision with the command itself
+        trove.patch_command("status", rename("status")).unwrap();
+    }
+}
@@ -0,0 +1,295 @@
+use crate::core::error::TroveError;
+use crate::core::trove::Trove;
+use crate::core::HoardCmd;
+
+/// Fields of a `HoardCmd` a query term can be matched against
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum QueryField {
+    Namespace,
+    Name,
+    Command,
+    Description,
+    Tag,
+    /// Matches if any of the fields above matches
+    Any,
+}
+
+impl QueryField {
+    fn parse(field: &str) -> Option<Self> {
+        match field {
+            "namespace" | "ns" => Some(Self::Namespace),
+            "name" => Some(Self::Name),
+            "command" | "cmd" => Some(Self::Command),
+            "description" | "desc" => Some(Self::Description),
+            "tag" | "tags" => Some(Self::Tag),
+            _ => None,
+        }
+    }
+}
+
+/// How the value of a query term is compared to a field
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum QueryOperator {
+    /// `field:value`, the field has to be equal to the value
+    Exact,
+    /// `field~value`, the field has to contain the value
+    Contains,
+}
+
+/// A single `field:value` or `field~value` expression of a query
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct QueryTerm {
+    pub field: QueryField,
+    pub operator: QueryOperator,
+    pub value: String,
+}
+
+impl QueryTerm {
+    fn matches_value(&self, field_value: &str) -> bool {
+        match self.operator {
+            QueryOperator::Exact => field_value == self.value,
+            QueryOperator::Contains => field_value.contains(&self.value),
+        }
+    }
+
+    /// Check if the term matches the given command
+    pub fn matches(&self, command: &HoardCmd) -> bool {
+        match self.field {
+            QueryField::Namespace => self.matches_value(&command.namespace),
+            QueryField::Name => self.matches_value(&command.name),
+            QueryField::Command => self.matches_value(&command.command),
+            QueryField::Description => self.matches_value(&command.description),
+            QueryField::Tag => command.tags.iter().any(|tag| self.matches_value(tag)),
+            QueryField::Any => {
+                self.matches_value(&command.namespace)
+                    || self.matches_value(&command.name)
+                    || self.matches_value(&command.command)
+                    || self.matches_value(&command.description)
+                    || command.tags.iter().any(|tag| self.matches_value(tag))
+            }
+        }
+    }
+}
+
+/// A parsed query of the hoard filter language
+///
+/// A query is a whitespace separated list of terms which all have to match (implicit AND)
+/// - `field:value` matches if the field is exactly `value`
+/// - `field~value` matches if the field contains `value`
+/// - `value` without a field matches if any field contains `value`
+///
+/// Supported fields are `namespace` (`ns`), `name`, `command` (`cmd`), `description` (`desc`) and `tag` (`tags`).
+/// Values containing whitespaces can be wrapped in double quotes, e.g. `description~"list all"`.
+/// All comparisons are case sensitive.
+#[derive(Debug, Clone, Default, PartialEq, Eq)]
+pub struct Query {
+    pub terms: Vec<QueryTerm>,
+}
+
+impl Query {
+    /// Parse a query string into a `Query`
+    ///
+    /// Returns `TroveError::QueryParse` pointing at the offending token if the query is malformed
+    pub fn parse(query: &str) -> Result<Self, TroveError> {
+        let terms = tokenize(query)?
+            .into_iter()
+            .map(|(position, token)| parse_term(position, &token))
+            .collect::<Result<Vec<_>, _>>()?;
+        Ok(Self { terms })
+    }
+
+    /// Check if all terms of the query match the given command
+    pub fn matches(&self, command: &HoardCmd) -> bool {
+        self.terms.iter().all(|term| term.matches(command))
+    }
+}
+
+/// Split a query string at whitespaces, keeping double quoted sections together
+/// Returns each token together with the position it starts at
+fn tokenize(query: &str) -> Result<Vec<(usize, String)>, TroveError> {
+    let mut tokens = Vec::new();
+    let mut current = String::new();
+    let mut start = 0;
+    let mut quote_start = None;
+    for (i, c) in query.char_indices() {
+        match c {
+            '"' if quote_start.is_none() => {
+                if current.is_empty() {
+                    start = i;
+                }
+                quote_start = Some(i);
+                current.push(c);
+            }
+            '"' => {
+                quote_start = None;
+                current.push(c);
+            }
+            c if c.is_whitespace() && quote_start.is_none() => {
+                if !current.is_empty() {
+                    tokens.push((start, std::mem::take(&mut current)));
+                }
+            }
+            c => {
+                if current.is_empty() {
+                    start = i;
+                }
+                current.push(c);
+            }
+        }
+    }
+    if quote_start.is_some() {
+        return Err(TroveError::QueryParse {
+            token: current,
+            position: start,
+            reason: "unterminated quote".to_string(),
+        });
+    }
+    if !current.is_empty() {
+        tokens.push((start, current));
+    }
+    Ok(tokens)
+}
+
+fn parse_term(position: usize, token: &str) -> Result<QueryTerm, TroveError> {
+    let parse_error = |reason: &str| TroveError::QueryParse {
+        token: token.to_string(),
+        position,
+        reason: reason.to_string(),
+    };
+    // The first `:` or `~` outside of quotes separates the field from the value
+    let separator = token
+        .char_indices()
+        .take_while(|(_, c)| *c != '"')
+        .find(|(_, c)| *c == ':' || *c == '~');
+
+    let (field, operator, value) = match separator {
+        Some((i, c)) => {
+            let field = &token[..i];
+            if field.is_empty() {
+                return Err(parse_error("missing field name"));
+            }
+            let field = QueryField::parse(field).ok_or_else(|| {
+                parse_error(
+                    "unknown field, expected one of namespace, name, command, description or tag",
+                )
+            })?;
+            let operator = if c == ':' {
+                QueryOperator::Exact
+            } else {
+                QueryOperator::Contains
+            };
+            (field, operator, &token[i + 1..])
+        }
+        None => (QueryField::Any, QueryOperator::Contains, token),
+    };
+
+    let value = value
+        .strip_prefix('"')
+        .and_then(|v| v.strip_suffix('"'))
+        .unwrap_or(value);
+    if value.is_empty() {
+        return Err(parse_error("missing value"));
+    }
+    Ok(QueryTerm {
+        field,
+        operator,
+        value: value.to_string(),
+    })
+}
+
+impl Trove {
+    #[allow(dead_code)]
+    /// Filter the commands of the trove with a query of the hoard filter language
+    ///
+    /// Example: `namespace:aws tag:prod command~kubectl`
+    ///
+    /// See `Query` for the supported syntax.
+    /// Returns `TroveError::QueryParse` if the query is malformed
+    pub fn query(&self, q: &str) -> Result<Vec<&HoardCmd>, TroveError> {
+        let mut query = Query::parse(q)?;
+        // Exact namespace terms may use a namespace alias
+        for term in &mut query.terms {
+            if term.field == QueryField::Namespace && term.operator == QueryOperator::Exact {
+                term.value = self.resolve_namespace_alias(&term.value).to_string();
+            }
+        }
+        Ok(self.commands.iter().filter(|c| query.matches(c)).collect())
+    }
+}
+
+#[cfg(test)]
+mod test_commands {
+    use super::*;
+
+    fn trove() -> Trove {
+        let commands = vec![
+            HoardCmd::default()
+                .with_name("pods")
+                .with_namespace("aws")
+                .with_command("kubectl get pods")
+                .with_description("list all pods")
+                .with_tags_raw("prod,k8s"),
+            HoardCmd::default()
+                .with_name("nodes")
+                .with_namespace("aws")
+                .with_command("kubectl get nodes")
+                .with_tags_raw("dev"),
+            HoardCmd::default()
+                .with_name("status")
+                .with_namespace("git")
+                .with_command("git status")
+                .with_tags_raw("prod"),
+        ];
+        Trove::from_commands(&commands)
+    }
+
+    #[test]
+    fn query_implicit_and() {
+        let trove = trove();
+        let result = trove
+            .query("namespace:aws tag:prod command~kubectl")
+            .unwrap();
+        assert_eq!(1, result.len());
+        assert_eq!("pods", result[0].name);
+    }
+
+    #[test]
+    fn query_bare_and_quoted_terms() {
+        let trove = trove();
+        assert_eq!(3, trove.query("").unwrap().len());
+        assert_eq!(2, trove.query("kubectl").unwrap().len());
+        let result = trove.query("description~\"all pods\"").unwrap();
+        assert_eq!(1, result.len());
+    }
+
+    #[test]
+    fn query_resolves_namespace_alias() {
+        let mut trove = trove();
+        trove.set_namespace_alias("amazon", "aws").unwrap();
+        assert_eq!(2, trove.query("namespace:amazon").unwrap().len());
+    }
+
+    #[test]
+    fn query_exact_does_not_match_substring() {
+        let trove = trove();
+        assert!(trove.query("namespace:aw").unwrap().is_empty());
+        assert_eq!(2, trove.query("namespace~aw").unwrap().len());
+    }
+
+    #[test]
+    fn query_parse_errors_point_at_token() {
+        let trove = trove();
+        match trove.query("tag:prod foo:bar") {
+            Err(TroveError::QueryParse {
+                token, position, ..
+            }) => {
+                assert_eq!("foo:bar", token);
+                assert_eq!(9, position);
+            }
+            _ => panic!("expected a parse error"),
+        }
+        assert!(trove.query("name:").is_err());
+        assert!(trove.query(":foo").is_err());
+        assert!(trove.query("desc~\"unterminated").is_err());
+    }
+}
@@ -0,0 +1,291 @@
+use anyhow::anyhow;
+use log::warn;
+
+use crate::core::error::TroveError;
+use crate::core::events::TroveEvent;
+use crate::core::trove::Trove;
+
+/// Prefix of a link from one command to another, e.g. `hoard:git/status`
+const LINK_PREFIX: &str = "hoard:";
+
+/// Returns the start and the `namespace/name` target of all `hoard:` links in `command`
+///
+/// A link ends at the first whitespace, quote or shell separator
+fn links(command: &str) -> impl Iterator<Item = (usize, &str)> {
+    command.match_indices(LINK_PREFIX).filter_map(|(i, _)| {
+        let target = &command[i + LINK_PREFIX.len()..];
+        let end = target
+            .find(|c: char| c.is_whitespace() || "\"'`;|&()".contains(c))
+            .unwrap_or(target.len());
+        Some((i, &target[..end])).filter(|(_, target)| target.contains('/'))
+    })
+}
+
+impl Trove {
+    #[allow(dead_code)]
+    /// Returns the qualified names (`namespace/name`) of all commands linking to the command `name` in `namespace`
+    ///
+    /// Commands reference each other with `hoard:namespace/name` links in their command string.
+    /// Namespace aliases are resolved on both sides. The result is sorted
+    pub fn references_to(&self, namespace: &str, name: &str) -> Vec<String> {
+        let namespace = self.resolve_namespace_alias(namespace);
+        let mut references: Vec<String> = self
+            .commands
+            .iter()
+            .filter(|c| !(c.namespace == namespace && c.name == name))
+            .filter(|c| {
+                links(&c.command).any(|(_, target)| self.is_link_to(target, namespace, name))
+            })
+            .map(|c| format!("{}/{}", c.namespace, c.name))
+            .collect();
+        references.sort_unstable();
+        references
+    }
+
+    /// Check if the link `target` points at the command `name` in the canonical `namespace`
+    fn is_link_to(&self, target: &str, namespace: &str, name: &str) -> bool {
+        target
+            .rsplit_once('/')
+            .is_some_and(|(ns, n)| n == name && self.resolve_namespace_alias(ns) == namespace)
+    }
+
+    #[allow(dead_code)]
+    /// Move the command `name` of `namespace` to `new_namespace`, renaming it to `new_name`
+    ///
+    /// If `cascade` is set, `hoard:` links of other commands pointing at the command are rewritten to its new
+    /// qualified name. Otherwise they are left dangling.
+    /// Returns the number of rewritten links, `TroveError::CommandNotFound` if there is no such command
+    /// and `TroveError::CommandExists` if the new name is already taken
+    pub fn move_command(
+        &mut self,
+        namespace: &str,
+        name: &str,
+        new_namespace: &str,
+        new_name: &str,
+        cascade: bool,
+    ) -> Result<usize, TroveError> {
+        let namespace = self.resolve_namespace_alias(namespace).to_string();
+        let new_namespace = self.resolve_namespace_alias(new_namespace).to_string();
+        let position = self
+            .commands
+            .iter()
+            .position(|c| c.namespace == namespace && c.name == name)
+            .ok_or_else(|| TroveError::CommandNotFound(format!("{namespace}/{name}")))?;
+        if (namespace.as_str(), name) == (new_namespace.as_str(), new_name) {
+            return Ok(0);
+        }
+        let moved = self.commands[position]
+            .clone()
+            .with_namespace(&new_namespace)
+            .with_name(new_name);
+        if self.get_command_collision(&moved).is_some() {
+            return Err(TroveError::CommandExists(format!(
+                "{new_namespace}/{new_name}"
+            )));
+        }
+        self.emit(|| TroveEvent::Updated {
+            namespace: namespace.clone(),
+            name: name.to_string(),
+            command: moved.clone(),
+        });
+        self.commands[position] = moved;
+        self.add_namespace(&new_namespace);
+
+        let mut rewritten = 0;
+        if cascade {
+            let new_target = format!("{new_namespace}/{new_name}");
+            for i in 0..self.commands.len() {
+                let command = &self.commands[i].command;
+                let mut updated = String::with_capacity(command.len());
+                let mut end = 0;
+                for (start, target) in links(command) {
+                    if self.is_link_to(target, &namespace, name) {
+                        updated.push_str(&command[end..start]);
+                        updated.push_str(LINK_PREFIX);
+                        updated.push_str(&new_target);
+                        end = start + LINK_PREFIX.len() + target.len();
+                        rewritten += 1;
+                    }
+                }
+                if end > 0 {
+                    updated.push_str(&command[end..]);
+                    self.commands[i].command = updated;
+                    let command = &self.commands[i];
+                    self.emit(|| TroveEvent::Updated {
+                        namespace: command.namespace.clone(),
+                        name: command.name.clone(),
+                        command: command.clone(),
+                    });
+                }
+            }
+        }
+        Ok(rewritten)
+    }
+
+    #[allow(dead_code)]
+    /// Rename the command `name` of `namespace` to `new_name`, see `Trove::move_command`
+    pub fn rename_command(
+        &mut self,
+        namespace: &str,
+        name: &str,
+        new_name: &str,
+        cascade: bool,
+    ) -> Result<usize, TroveError> {
+        self.move_command(namespace, name, namespace, new_name, cascade)
+    }
+
+    #[allow(dead_code)]
+    /// Rename the command `old_name` to `new_name` within its namespace, keeping all other fields
+    ///
+    /// The new name may exist in other namespaces. Links to the command are not rewritten.
+    /// Returns `TroveError::CommandNotFound` if there is no such command, `TroveError::AmbiguousCommand`
+    /// if several namespaces have a command named `old_name` and `TroveError::CommandExists` if the new name is taken
+    pub fn rename_command_by_name(
+        &mut self,
+        old_name: &str,
+        new_name: &str,
+    ) -> Result<(), TroveError> {
+        let namespaces: Vec<String> = self
+            .commands
+            .iter()
+            .filter(|c| c.name == old_name)
+            .map(|c| c.namespace.clone())
+            .collect();
+        match namespaces.as_slice() {
+            [] => Err(TroveError::CommandNotFound(old_name.to_string())),
+            [namespace] => {
+                let namespace = namespace.clone();
+                self.rename_command(&namespace, old_name, new_name, false)
+                    .map(|_| ())
+            }
+            _ => Err(TroveError::AmbiguousCommand {
+                name: old_name.to_string(),
+                namespaces,
+            }),
+        }
+    }
+
+    #[allow(dead_code)]
+    /// Remove a command from the trove collection like `remove_command`, checking for references to it first
+    ///
+    /// If other commands link to the removed command, the removal is refused with an error if `refuse_referenced` is set.
+    /// Otherwise a warning listing the dangling references is logged and the command is removed
+    pub fn remove_command_checked(
+        &mut self,
+        name: &str,
+        refuse_referenced: bool,
+    ) -> Result<(), anyhow::Error> {
+        let references: Vec<String> = self
+            .commands
+            .iter()
+            .filter(|c| c.name == name)
+            .flat_map(|c| self.references_to(&c.namespace, &c.name))
+            .collect();
+        if !references.is_empty() {
+            if refuse_referenced {
+                return Err(anyhow!(
+                    "Command [{}] is referenced by {}",
+                    name,
+                    references.join(", ")
+                ));
+            }
+            warn!(
+                "Removing command [{}] leaves dangling references in {}",
+                name,
+                references.join(", ")
+            );
+        }
+        self.remove_command(name)
+    }
+}
+
+#[cfg(test)]
+mod test_commands {
+    use super::*;
+    use crate::core::HoardCmd;
+
+    fn trove() -> Trove {
+        let commands = vec![
+            HoardCmd::default()
+                .with_name("status")
+                .with_namespace("git")
+                .with_command("git status"),
+            HoardCmd::default()
+                .with_name("check")
+                .with_namespace("daily")
+                .with_command("hoard:git/status && hoard:vcs/status"),
+            HoardCmd::default()
+                .with_name("other")
+                .with_namespace("daily")
+                .with_command("echo 'hoard:git/statuses'"),
+        ];
+        let mut trove = Trove::from_commands(&commands);
+        trove.set_namespace_alias("vcs", "git").unwrap();
+        trove
+    }
+
+    #[test]
+    fn references_to() {
+        let trove = trove();
+        assert_eq!(vec!["daily/check"], trove.references_to("git", "status"));
+        assert_eq!(vec!["daily/check"], trove.references_to("vcs", "status"));
+        assert!(trove.references_to("daily", "check").is_empty());
+    }
+
+    #[test]
+    fn remove_referenced_command() {
+        let mut trove = trove();
+        assert!(trove.remove_command_checked("status", true).is_err());
+        assert_eq!(3, trove.commands.len());
+        trove.remove_command_checked("status", false).unwrap();
+        assert_eq!(2, trove.commands.len());
+        trove.remove_command_checked("check", true).unwrap();
+    }
+
+    #[test]
+    fn rename_cascades_to_links() {
+        let mut trove = trove();
+        assert_eq!(
+            2,
+            trove
+                .move_command("vcs", "status", "scm", "st", true)
+                .unwrap()
+        );
+        assert_eq!("hoard:scm/st && hoard:scm/st", trove.commands[1].command);
+        assert_eq!("echo 'hoard:git/statuses'", trove.commands[2].command);
+        assert_eq!(vec!["daily/check"], trove.references_to("scm", "st"));
+
+        assert_eq!(0, trove.rename_command("scm", "st", "s", false).unwrap());
+        assert!(trove.references_to("scm", "s").is_empty());
+        assert!(matches!(
+            trove.rename_command("daily", "check", "other", true),
+            Err(TroveError::CommandExists(_))
+        ));
+        assert!(matches!(
+            trove.rename_command("daily", "missing", "m", true),
+            Err(TroveError::CommandNotFound(_))
+        ));
+    }
+
+    #[test]
+    fn rename_by_name() {
+        let mut trove = trove();
+        // Names of other namespaces may be reused
+        trove.rename_command_by_name("other", "status").unwrap();
+        assert_eq!("status", trove.commands[2].name);
+        assert_eq!("echo 'hoard:git/statuses'", trove.commands[2].command);
+        assert!(matches!(
+            trove.rename_command_by_name("status", "s"),
+            Err(TroveError::AmbiguousCommand { .. })
+        ));
+        assert!(matches!(
+            trove.rename_command_by_name("check", "status"),
+            Err(TroveError::CommandExists(_))
+        ));
+        assert!(matches!(
+            trove.rename_command_by_name("missing", "m"),
+            Err(TroveError::CommandNotFound(_))
+        ));
+        assert_eq!("check", trove.commands[1].name);
+    }
+}
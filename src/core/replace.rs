@@ -0,0 +1,150 @@
+use regex::Regex;
+use std::time;
+
+use crate::core::error::TroveError;
+use crate::core::trove::Trove;
+
+/// A change of a command string by `Trove::replace_in_commands`
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct Replacement {
+    pub name: String,
+    pub namespace: String,
+    pub before: String,
+    pub after: String,
+}
+
+/// Search pattern for a search-and-replace over command strings
+enum Pattern<'a> {
+    Literal(&'a str),
+    Regex(Regex),
+}
+
+impl<'a> Pattern<'a> {
+    fn new(find: &'a str, regex: bool) -> Result<Self, TroveError> {
+        if regex {
+            Ok(Self::Regex(Regex::new(find)?))
+        } else {
+            Ok(Self::Literal(find))
+        }
+    }
+
+    /// Returns the replaced string, or `None` if the pattern does not match
+    fn replace(&self, haystack: &str, replace: &str) -> Option<String> {
+        match self {
+            Self::Literal(find) if !find.is_empty() && haystack.contains(find) => {
+                Some(haystack.replace(find, replace))
+            }
+            Self::Regex(re) if re.is_match(haystack) => {
+                Some(re.replace_all(haystack, replace).to_string())
+            }
+            _ => None,
+        }
+    }
+}
+
+impl Trove {
+    #[allow(dead_code)]
+    /// Preview a search-and-replace over all command strings without changing the trove
+    ///
+    /// If `regex` is set, `find` is a regular expression and `replace` may reference capture groups (`$1`)
+    /// Returns every command that would change
+    pub fn preview_replace_in_commands(
+        &self,
+        find: &str,
+        replace: &str,
+        regex: bool,
+    ) -> Result<Vec<Replacement>, TroveError> {
+        let pattern = Pattern::new(find, regex)?;
+        Ok(self
+            .commands
+            .iter()
+            .filter_map(|c| {
+                pattern
+                    .replace(&c.command, replace)
+                    .filter(|after| *after != c.command)
+                    .map(|after| Replacement {
+                        name: c.name.clone(),
+                        namespace: c.namespace.clone(),
+                        before: c.command.clone(),
+                        after,
+                    })
+            })
+            .collect())
+    }
+
+    #[allow(dead_code)]
+    /// Apply a search-and-replace over all command strings of the trove
+    ///
+    /// If `regex` is set, `find` is a regular expression and `replace` may reference capture groups (`$1`)
+    /// Commands where the pattern does not match are not touched.
+    /// Returns the number of changed commands
+    pub fn replace_in_commands(
+        &mut self,
+        find: &str,
+        replace: &str,
+        regex: bool,
+    ) -> Result<usize, TroveError> {
+        let pattern = Pattern::new(find, regex)?;
+        let mut changed = 0;
+        for position in 0..self.commands.len() {
+            if let Some(after) = pattern.replace(&self.commands[position].command, replace) {
+                if after != self.commands[position].command {
+                    let mut command = self.commands[position].clone();
+                    command.command = after;
+                    command.modified = time::SystemTime::now();
+                    self.replace_at(position, command);
+                    changed += 1;
+                }
+            }
+        }
+        Ok(changed)
+    }
+}
+
+#[cfg(test)]
+mod test_commands {
+    use super::*;
+    use crate::core::HoardCmd;
+
+    fn trove() -> Trove {
+        let command1 = HoardCmd::default()
+            .with_name("pods")
+            .with_namespace("k8s")
+            .with_command("kubectl get pods --all");
+        let command2 = HoardCmd::default()
+            .with_name("nodes")
+            .with_namespace("k8s")
+            .with_command("kubectl get nodes");
+        Trove::from_commands(&[command1, command2])
+    }
+
+    #[test]
+    fn replace_literal() {
+        let mut trove = trove();
+        assert_eq!(1, trove.replace_in_commands("--all", "-A", false).unwrap());
+        assert_eq!("kubectl get pods -A", trove.commands[0].command);
+        assert_eq!("kubectl get nodes", trove.commands[1].command);
+    }
+
+    #[test]
+    fn replace_regex() {
+        let mut trove = trove();
+        let changed = trove
+            .replace_in_commands(r"^kubectl (\w+)", "k $1", true)
+            .unwrap();
+        assert_eq!(2, changed);
+        assert_eq!("k get nodes", trove.commands[1].command);
+        assert!(trove.replace_in_commands("(", "", true).is_err());
+    }
+
+    #[test]
+    fn preview_does_not_mutate() {
+        let trove = trove();
+        let preview = trove
+            .preview_replace_in_commands("get", "describe", false)
+            .unwrap();
+        assert_eq!(2, preview.len());
+        assert_eq!("kubectl describe nodes", preview[1].after);
+        assert_eq!("kubectl get nodes", trove.commands[1].command);
+    }
+}
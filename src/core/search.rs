@@ -0,0 +1,326 @@
+use regex::Regex;
+use std::time::{Duration, SystemTime};
+
+use crate::core::error::TroveError;
+use crate::core::trove::Trove;
+use crate::core::HoardCmd;
+
+/// One day, in seconds as `Duration::from_hours` is too new for the Rust versions hoard builds with
+#[allow(clippy::duration_suboptimal_units)]
+pub const DAY: Duration = Duration::from_secs(24 * 60 * 60);
+
+/// Weights of the frecency ranking of `Trove::search_frecency`
+///
+/// The score of a command is
+/// `match_weight * fuzzy + frequency_weight * ln(1 + usage_count) + recency_weight * 0.5^(age / recency_half_life)`
+/// where `fuzzy` is the fuzzy match score (`0..=1`) and `age` is the time since the command was last used.
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub struct FrecencyWeights {
+    /// Weight of how well the query matches the command
+    pub match_weight: f64,
+    /// Weight of how often the command was used
+    pub frequency_weight: f64,
+    /// Weight of how recently the command was used
+    pub recency_weight: f64,
+    /// Time after which the recency boost of a command is halved
+    pub recency_half_life: Duration,
+}
+
+impl Default for FrecencyWeights {
+    fn default() -> Self {
+        Self {
+            match_weight: 10.0,
+            frequency_weight: 1.0,
+            recency_weight: 2.0,
+            recency_half_life: 7 * DAY,
+        }
+    }
+}
+
+impl FrecencyWeights {
+    /// Frequency and recency part of the score of `command`
+    fn boost(&self, command: &HoardCmd, now: SystemTime) -> f64 {
+        #[allow(clippy::cast_precision_loss)]
+        let frequency = (command.usage_count as f64).ln_1p();
+        let age = now
+            .duration_since(command.last_used)
+            .unwrap_or(Duration::ZERO);
+        let recency = 0.5_f64.powf(age.as_secs_f64() / self.recency_half_life.as_secs_f64());
+        self.frequency_weight
+            .mul_add(frequency, self.recency_weight * recency)
+    }
+}
+
+/// Score how well `query` fuzzy matches `candidate`, ignoring case
+///
+/// All characters of `query` have to appear in `candidate` in order. Matches of consecutive characters
+/// and matches at the start of a word score higher.
+/// Returns a score between `0` and `1`, or `None` if `query` does not match
+pub fn fuzzy_score(query: &str, candidate: &str) -> Option<f64> {
+    let query: Vec<char> = query.to_lowercase().chars().collect();
+    if query.is_empty() {
+        return Some(1.0);
+    }
+    let candidate: Vec<char> = candidate.to_lowercase().chars().collect();
+    let mut points = 0_usize;
+    let mut position = 0;
+    let mut previous_match: Option<usize> = None;
+    for q in &query {
+        let found = candidate[position..].iter().position(|c| c == q)? + position;
+        points += 1;
+        if previous_match.is_some_and(|p| p + 1 == found) {
+            points += 1;
+        }
+        if found == 0 || !candidate[found - 1].is_alphanumeric() {
+            points += 1;
+        }
+        previous_match = Some(found);
+        position = found + 1;
+    }
+    #[allow(clippy::cast_precision_loss)]
+    let score = points as f64 / (3 * query.len()) as f64;
+    Some(score.min(1.0))
+}
+
+/// Best fuzzy match score of `query` over name, command, description and tags of `command`
+fn command_score(query: &str, command: &HoardCmd) -> Option<f64> {
+    [
+        command.name.as_str(),
+        command.command.as_str(),
+        command.description.as_str(),
+    ]
+    .into_iter()
+    .chain(command.tags())
+    .filter_map(|field| fuzzy_score(query, field))
+    .reduce(f64::max)
+}
+
+/// Scale of the integer scores of `Trove::fuzzy_search`, the score of a perfect match
+const FUZZY_SCORE_SCALE: f64 = 1000.0;
+
+impl Trove {
+    #[allow(dead_code)]
+    /// Fuzzy search the name, command, description and tags of all commands, ranked by how well they match
+    ///
+    /// Returns the matching commands with their score, best matches first. Scores range from `0` to `1000`.
+    /// Commands with equal scores keep their order in the trove. An empty query returns all commands with a score of `0`
+    pub fn fuzzy_search(&self, query: &str) -> Vec<(&HoardCmd, i64)> {
+        if query.is_empty() {
+            return self.commands.iter().map(|c| (c, 0)).collect();
+        }
+        let mut scored: Vec<(&HoardCmd, i64)> = self
+            .commands
+            .iter()
+            .filter_map(|c| {
+                #[allow(clippy::cast_possible_truncation)]
+                command_score(query, c).map(|score| (c, (score * FUZZY_SCORE_SCALE).round() as i64))
+            })
+            .collect();
+        scored.sort_by_key(|(_, score)| std::cmp::Reverse(*score));
+        scored
+    }
+
+    #[allow(dead_code)]
+    /// Fuzzy search the commands of the trove, ranked by match score, usage count and last usage combined
+    ///
+    /// Uses the default `FrecencyWeights`, see `Trove::search_frecency_with`
+    pub fn search_frecency(&self, query: &str) -> Vec<&HoardCmd> {
+        self.search_frecency_with(query, &FrecencyWeights::default())
+    }
+
+    #[allow(dead_code)]
+    /// Fuzzy search the commands of the trove, ranked by a frecency score weighted with `weights`
+    ///
+    /// Commands not matching `query` are left out. Commands with equal scores are sorted by name
+    pub fn search_frecency_with(&self, query: &str, weights: &FrecencyWeights) -> Vec<&HoardCmd> {
+        let now = SystemTime::now();
+        let mut scored: Vec<(f64, &HoardCmd)> = self
+            .commands
+            .iter()
+            .filter_map(|c| {
+                command_score(query, c).map(|score| {
+                    (
+                        weights.match_weight.mul_add(score, weights.boost(c, now)),
+                        c,
+                    )
+                })
+            })
+            .collect();
+        scored.sort_by(|a, b| b.0.total_cmp(&a.0).then_with(|| a.1.name.cmp(&b.1.name)));
+        scored.into_iter().map(|(_, c)| c).collect()
+    }
+
+    #[allow(dead_code)]
+    /// Get all commands ranked by how often and how recently they were used
+    ///
+    /// The score of a command is `ln(1 + usage_count) + 2 * 0.5^(age / 1 week)`, where `age` is the time since
+    /// the command was last used, i.e. the frecency part of the score of `search_frecency` with the default weights.
+    /// Commands that were never used are listed last. Commands with equal scores are sorted by name
+    pub fn by_frecency(&self) -> Vec<&HoardCmd> {
+        let weights = FrecencyWeights::default();
+        let now = SystemTime::now();
+        let mut scored: Vec<(bool, f64, &HoardCmd)> = self
+            .commands
+            .iter()
+            .map(|c| (c.usage_count > 0, weights.boost(c, now), c))
+            .collect();
+        scored.sort_by(|a, b| {
+            b.0.cmp(&a.0)
+                .then_with(|| b.1.total_cmp(&a.1))
+                .then_with(|| a.2.name.cmp(&b.2.name))
+        });
+        scored.into_iter().map(|(_, _, c)| c).collect()
+    }
+
+    #[allow(dead_code)]
+    /// Find all commands whose command string matches the regular expression `pattern`
+    ///
+    /// See `Trove::search_regex_with` to match descriptions as well.
+    /// Returns `TroveError::Regex` if `pattern` is no valid regular expression
+    pub fn search_regex(&self, pattern: &str) -> Result<Vec<&HoardCmd>, TroveError> {
+        self.search_regex_with(pattern, false)
+    }
+
+    #[allow(dead_code)]
+    /// Find all commands whose command string, or description if `include_description` is set, matches `pattern`
+    ///
+    /// Uses the syntax of the `regex` crate, e.g. `kubectl .* -n \w+`. Matches may be anywhere in the field.
+    /// Returns `TroveError::Regex` if `pattern` is no valid regular expression
+    pub fn search_regex_with(
+        &self,
+        pattern: &str,
+        include_description: bool,
+    ) -> Result<Vec<&HoardCmd>, TroveError> {
+        let re = Regex::new(pattern)?;
+        Ok(self
+            .commands
+            .iter()
+            .filter(|c| {
+                re.is_match(&c.command) || (include_description && re.is_match(&c.description))
+            })
+            .collect())
+    }
+}
+
+#[cfg(test)]
+mod test_commands {
+    use super::*;
+
+    fn command(name: &str, usage_count: usize, age: Duration) -> HoardCmd {
+        let mut command = HoardCmd::default()
+            .with_name(name)
+            .with_namespace("test")
+            .with_command(&format!("run {name}"));
+        command.usage_count = usage_count;
+        command.last_used = SystemTime::now() - age;
+        command
+    }
+
+    #[test]
+    fn fuzzy_scores() {
+        assert_eq!(None, fuzzy_score("xz", "kubectl"));
+        assert_eq!(Some(1.0), fuzzy_score("", "kubectl"));
+        let prefix = fuzzy_score("kub", "kubectl").unwrap();
+        let scattered = fuzzy_score("kbl", "kubectl").unwrap();
+        assert!(prefix > scattered);
+        assert!(fuzzy_score("GS", "git status").is_some());
+    }
+
+    #[test]
+    fn frecency_ranks_used_commands_first() {
+        let day = DAY;
+        let trove = Trove::from_commands(&[
+            command("deploy-old", 1, 300 * day),
+            command("deploy-daily", 50, Duration::ZERO),
+            command("status", 100, Duration::ZERO),
+        ]);
+        let names: Vec<&str> = trove
+            .search_frecency("deploy")
+            .iter()
+            .map(|c| c.name.as_str())
+            .collect();
+        assert_eq!(vec!["deploy-daily", "deploy-old"], names);
+
+        // Without match weight, only usage decides
+        let weights = FrecencyWeights {
+            match_weight: 0.0,
+            ..FrecencyWeights::default()
+        };
+        assert_eq!("status", trove.search_frecency_with("", &weights)[0].name);
+    }
+
+    #[test]
+    fn frecency_order() {
+        let day = DAY;
+        let trove = Trove::from_commands(&[
+            command("never", 0, Duration::ZERO),
+            command("stale", 20, 100 * day),
+            command("fresh", 3, Duration::ZERO),
+            command("frequent", 20, Duration::ZERO),
+        ]);
+        let names: Vec<&str> = trove
+            .by_frecency()
+            .iter()
+            .map(|c| c.name.as_str())
+            .collect();
+        assert_eq!(vec!["frequent", "fresh", "stale", "never"], names);
+    }
+
+    #[test]
+    fn regex_search() {
+        let trove = Trove::from_commands(&[
+            HoardCmd::default()
+                .with_name("pods")
+                .with_namespace("k8s")
+                .with_command("kubectl get pods -n default"),
+            HoardCmd::default()
+                .with_name("nodes")
+                .with_namespace("k8s")
+                .with_command("kubectl get nodes")
+                .with_description("run with -n kube-system"),
+        ]);
+        let names = |commands: Vec<&HoardCmd>| -> Vec<String> {
+            commands.iter().map(|c| c.name.clone()).collect()
+        };
+        let pattern = r"kubectl .* -n \w+|-n kube";
+        assert_eq!(vec!["pods"], names(trove.search_regex(pattern).unwrap()));
+        assert_eq!(
+            vec!["pods", "nodes"],
+            names(trove.search_regex_with(pattern, true).unwrap())
+        );
+        assert!(matches!(
+            trove.search_regex("kubectl ("),
+            Err(TroveError::Regex(_))
+        ));
+    }
+
+    #[test]
+    fn fuzzy_search_ranks_matches() {
+        let trove = Trove::from_commands(&[
+            HoardCmd::default()
+                .with_name("ps")
+                .with_namespace("docker")
+                .with_command("docker ps"),
+            HoardCmd::default()
+                .with_name("images")
+                .with_namespace("docker")
+                .with_command("docker images"),
+            HoardCmd::default()
+                .with_name("status")
+                .with_namespace("git")
+                .with_command("git status"),
+        ]);
+        let results = trove.fuzzy_search("dkr ps");
+        assert_eq!(1, results.len());
+        assert_eq!("docker ps", results[0].0.command);
+        assert!((1..=1000).contains(&results[0].1));
+
+        let results = trove.fuzzy_search("docker");
+        assert_eq!(2, results.len());
+        assert!(results[0].1 >= results[1].1);
+
+        let all = trove.fuzzy_search("");
+        assert_eq!(3, all.len());
+        assert!(all.iter().all(|(_, score)| *score == 0));
+    }
+}
@@ -0,0 +1,93 @@
+use console::{style, Color};
+use std::collections::HashMap;
+
+use crate::config::HoardConfig;
+use crate::core::fnv1a;
+
+/// Colors tags without a configured color are picked from, based on a hash of the tag
+const TAG_PALETTE: [Color; 6] = [
+    Color::Red,
+    Color::Green,
+    Color::Yellow,
+    Color::Blue,
+    Color::Magenta,
+    Color::Cyan,
+];
+
+fn parse_color(name: &str) -> Option<Color> {
+    match name.trim().to_lowercase().as_str() {
+        "black" => Some(Color::Black),
+        "red" => Some(Color::Red),
+        "green" => Some(Color::Green),
+        "yellow" => Some(Color::Yellow),
+        "blue" => Some(Color::Blue),
+        "magenta" | "purple" => Some(Color::Magenta),
+        "cyan" => Some(Color::Cyan),
+        "white" => Some(Color::White),
+        _ => None,
+    }
+}
+
+/// Pick a stable color for `tag` from `TAG_PALETTE`
+fn hashed_color(tag: &str) -> Color {
+    let hash = fnv1a(tag.as_bytes());
+    TAG_PALETTE[usize::try_from(hash % TAG_PALETTE.len() as u64).unwrap_or(0)]
+}
+
+/// Colors of tags as configured in `tag_colors` of the config
+///
+/// Invalid color names are left out with a warning
+pub fn configured_tag_colors(config: &HoardConfig) -> HashMap<String, Color> {
+    let mut colors = HashMap::new();
+    for (tag, name) in &config.tag_colors {
+        match parse_color(name) {
+            Some(color) => {
+                colors.insert(tag.clone(), color);
+            }
+            None => eprintln!("Warning: ignoring unknown color '{name}' for tag '{tag}'"),
+        }
+    }
+    colors
+}
+
+/// Get the color of `tag`, falling back to a hash based color for tags without a configured color
+pub fn tag_color(tag: &str, configured: &HashMap<String, Color>) -> Color {
+    configured
+        .get(tag)
+        .copied()
+        .unwrap_or_else(|| hashed_color(tag))
+}
+
+/// Join `tags` with `,`, each tag colored with `tag_color`
+pub fn colored_tags(tags: &[&str], configured: &HashMap<String, Color>) -> String {
+    tags.iter()
+        .map(|tag| style(tag).fg(tag_color(tag, configured)).to_string())
+        .collect::<Vec<_>>()
+        .join(",")
+}
+
+#[cfg(test)]
+mod test_commands {
+    use super::*;
+
+    #[test]
+    fn configured_colors_take_precedence() {
+        let mut config = HoardConfig::default();
+        config
+            .tag_colors
+            .insert("prod".to_string(), "Red".to_string());
+        config
+            .tag_colors
+            .insert("dev".to_string(), "not-a-color".to_string());
+        let configured = configured_tag_colors(&config);
+        assert_eq!(1, configured.len());
+        assert_eq!(Color::Red, tag_color("prod", &configured));
+        assert_eq!(hashed_color("dev"), tag_color("dev", &configured));
+    }
+
+    #[test]
+    fn hashed_colors_are_stable() {
+        assert_eq!(hashed_color("k8s"), hashed_color("k8s"));
+        assert!(TAG_PALETTE.contains(&hashed_color("")));
+    }
+}
@@ -1,17 +1,34 @@
 use anyhow::{anyhow, Result};
-use log::info;
+use chrono::{DateTime, Local, Utc};
+use log::{error, info, warn};
 use prettytable::{color, Attr, Cell, Row, Table};
-use serde::{Deserialize, Serialize};
+use serde::{Deserialize, Serialize, Serializer};
 
-use std::collections::HashSet;
+use std::cmp::Ordering;
+use std::collections::{HashMap, HashSet};
+use std::io::{self, BufReader, BufWriter, Read, Write};
 use std::{fs, path::Path, path::PathBuf};
 
 use crate::config::HoardConfig;
-use crate::core::error::HoardErr;
+use crate::core::error::{HoardErr, TroveError, TroveValidationError};
+use crate::core::events::{EventSink, TroveEvent};
+use crate::core::merge::MergeReport;
 use crate::core::parameters::Parameterized;
-use crate::core::HoardCmd;
+use crate::core::tag_colors::{colored_tags, configured_tag_colors};
+use crate::core::yaml::{with_style, StyledWriter, YamlStyle};
+use crate::core::{generate_id, HoardCmd, SuffixStrategy, DEFAULT_SUFFIX_LENGTH};
 
-const CARGO_VERSION: &str = env!("CARGO_PKG_VERSION");
+pub const CARGO_VERSION: &str = env!("CARGO_PKG_VERSION");
+/// Number of commands after which progress callbacks of bulk operations are called
+const PROGRESS_INTERVAL: usize = 100;
+/// Characters used to separate levels of a namespace hierarchy, e.g. `work.k8s` or `work/k8s`
+const NAMESPACE_SEPARATORS: [char; 2] = ['.', '/'];
+
+/// Attempts to find a unique random name suffix before trying longer suffixes
+const SUFFIX_ATTEMPTS: usize = 100;
+
+/// Keys of commands whose values are formatted by `YamlStyle`
+const YAML_STYLED_KEYS: &[&str] = &["command", "description"];
 
 /// Container for all stored hoard commands.
 /// A `treasure trove` of commands
@@ -21,12 +38,134 @@ const CARGO_VERSION: &str = env!("CARGO_PKG_VERSION");
 ///              To potentially support migrating older collections to new ones when breaking changes happen
 /// - `commands`: Vector of `HoardCmd`s, the stored commands
 /// - `namespaces`: Set of all namespaces used in the collection
+/// - `namespace_aliases`: Map of alias names to the canonical namespace they refer to
+/// - `favorites`: Set of ids of favorite commands. Not part of the trove file, but stored in a separate sidecar file
+/// - `options`: Behavior of the trove collection set from the config, see `TroveOptions`. Not part of the trove file
+/// - `events`: Receiver of the changes of the trove collection, see `Trove::with_event_sink`. Not part of the trove file
 #[derive(Debug, Serialize, Clone, Deserialize)]
 pub struct Trove {
     pub version: String,
+    #[serde(serialize_with = "serialize_commands_sorted")]
     pub commands: Vec<HoardCmd>,
-    #[serde(default)]
+    #[serde(default, serialize_with = "serialize_sorted")]
     pub namespaces: HashSet<String>,
+    #[serde(default, skip_serializing_if = "HashMap::is_empty")]
+    pub namespace_aliases: HashMap<String, String>,
+    #[serde(skip)]
+    pub favorites: HashSet<String>,
+    #[serde(skip)]
+    pub options: TroveOptions,
+    #[serde(skip)]
+    pub events: EventSink,
+}
+
+/// Serialize a set of strings sorted, so serialized trove files are stable across saves
+fn serialize_sorted<S: Serializer>(
+    set: &HashSet<String>,
+    serializer: S,
+) -> Result<S::Ok, S::Error> {
+    let mut sorted: Vec<&String> = set.iter().collect();
+    sorted.sort_unstable();
+    sorted.serialize(serializer)
+}
+
+/// Replace the file at `path` with the content written by `write`
+///
+/// The content is written to a temporary file in the same directory, synced to disk and then renamed to `path`,
+/// so `path` either keeps its previous content or has the complete new content, even if the process is killed.
+/// The permissions of an existing file at `path` are kept
+fn write_atomically(
+    path: &Path,
+    write: impl FnOnce(&mut BufWriter<fs::File>) -> Result<(), TroveError>,
+) -> Result<(), TroveError> {
+    let file_name = path.file_name().map_or_else(
+        || "trove".into(),
+        |name| name.to_string_lossy().into_owned(),
+    );
+    let temp_path = path.with_file_name(format!(".{file_name}.{}.tmp", std::process::id()));
+    let result = (|| {
+        let mut writer = BufWriter::new(fs::File::create(&temp_path)?);
+        write(&mut writer)?;
+        let file = writer
+            .into_inner()
+            .map_err(io::IntoInnerError::into_error)?;
+        file.sync_all()?;
+        if let Ok(metadata) = fs::metadata(path) {
+            fs::set_permissions(&temp_path, metadata.permissions())?;
+        }
+        fs::rename(&temp_path, path)?;
+        Ok(())
+    })();
+    if result.is_err() {
+        let _ = fs::remove_file(&temp_path);
+    } else if let Some(dir) = path.parent().filter(|dir| !dir.as_os_str().is_empty()) {
+        // Persist the rename, not supported on all platforms
+        let _ = fs::File::open(dir).and_then(|dir| dir.sync_all());
+    }
+    result
+}
+
+/// Order of commands in listings and trove files: by namespace, then by name
+///
+/// Within a namespace, commands with a persisted `order` come first, sorted by it
+fn listing_order(a: &HoardCmd, b: &HoardCmd) -> Ordering {
+    a.namespace
+        .cmp(&b.namespace)
+        .then_with(|| {
+            a.order
+                .unwrap_or(usize::MAX)
+                .cmp(&b.order.unwrap_or(usize::MAX))
+        })
+        .then_with(|| a.name.cmp(&b.name))
+}
+
+/// Serialize commands in listing order, so adding a command to a trove file results in a minimal diff
+fn serialize_commands_sorted<S: Serializer>(
+    commands: &[HoardCmd],
+    serializer: S,
+) -> Result<S::Ok, S::Error> {
+    let mut sorted: Vec<&HoardCmd> = commands.iter().collect();
+    sorted.sort_by(|a, b| listing_order(a, b));
+    sorted.serialize(serializer)
+}
+
+/// Check if `path` is a json file by its extension
+fn is_json_path(path: &Path) -> bool {
+    path.extension().is_some_and(|ext| ext == "json")
+}
+
+/// Behavior of a trove collection that is set from the config instead of being stored in the trove file
+///
+/// - `case_sensitive_namespaces`: If unset, namespaces only differing in case are the same namespace,
+///   keeping the spelling the namespace was first used with
+/// - `suffix_length`: Length of random name suffixes resolving name collisions
+/// - `suffix_charset`: Characters random name suffixes are picked from, alphanumeric characters if `None`
+/// - `suffix_strategy`: How name suffixes resolving name collisions are picked
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct TroveOptions {
+    pub case_sensitive_namespaces: bool,
+    pub suffix_length: usize,
+    pub suffix_charset: Option<String>,
+    pub suffix_strategy: SuffixStrategy,
+}
+
+impl Default for TroveOptions {
+    fn default() -> Self {
+        Self {
+            case_sensitive_namespaces: true,
+            suffix_length: DEFAULT_SUFFIX_LENGTH,
+            suffix_charset: None,
+            suffix_strategy: SuffixStrategy::default(),
+        }
+    }
+}
+
+/// A checkpoint of a trove collection taken by `Trove::snapshot`, to be restored with `Trove::restore`
+///
+/// The representation is private, so it can change without affecting callers
+#[derive(Debug, Clone)]
+pub struct TroveSnapshot {
+    trove: Trove,
 }
 
 impl Default for Trove {
@@ -36,6 +175,10 @@ impl Default for Trove {
             version: CARGO_VERSION.to_string(),
             commands: Vec::new(),
             namespaces: HashSet::new(),
+            namespace_aliases: HashMap::new(),
+            favorites: HashSet::new(),
+            options: TroveOptions::default(),
+            events: EventSink::default(),
         }
     }
 }
@@ -54,41 +197,293 @@ impl Trove {
             version: CARGO_VERSION.to_string(),
             commands: commands.to_vec(),
             namespaces,
+            namespace_aliases: HashMap::new(),
+            favorites: HashSet::new(),
+            options: TroveOptions::default(),
+            events: EventSink::default(),
+        }
+    }
+
+    #[allow(dead_code)]
+    /// Create a new Trove from plain text with one command per line, all in `namespace`
+    ///
+    /// Empty lines are skipped. Lines starting with `#` become the description of the following command,
+    /// consecutive comment lines are joined with a space.
+    /// Names are generated from the program name of each command, e.g. `git`, `git-2`, ...
+    /// Commands that appear more than once are only imported the first time
+    pub fn from_lines(lines: &str, namespace: &str) -> Self {
+        let mut trove = Self::default();
+        let mut description: Vec<&str> = Vec::new();
+        for line in lines.lines().map(str::trim) {
+            if line.is_empty() {
+                continue;
+            }
+            if let Some(comment) = line.strip_prefix('#') {
+                description.push(comment.trim());
+                continue;
+            }
+            let description = std::mem::take(&mut description).join(" ");
+            if trove.commands.iter().any(|c| c.command == line) {
+                continue;
+            }
+            let program = line
+                .split_whitespace()
+                .next()
+                .and_then(|word| word.rsplit('/').find(|part| !part.is_empty()))
+                .unwrap_or("command");
+            let mut command = HoardCmd::default()
+                .with_id(&generate_id())
+                .with_name(program)
+                .with_namespace(namespace)
+                .with_command(line)
+                .with_description(&description);
+            let mut suffix = 1;
+            while trove.get_command_collision(&command).is_some() {
+                suffix += 1;
+                command = command.with_name(&format!("{program}-{suffix}"));
+            }
+            trove.commands.push(command);
+        }
+        trove.rebuild_namespaces();
+        trove
+    }
+
+    /// Set the options of the trove collection from `config`, see `TroveOptions`
+    pub fn apply_config(&mut self, config: &HoardConfig) {
+        self.options = TroveOptions {
+            case_sensitive_namespaces: config.case_sensitive_namespaces,
+            suffix_length: config.suffix_length.unwrap_or(DEFAULT_SUFFIX_LENGTH),
+            suffix_charset: config.suffix_charset.clone(),
+            suffix_strategy: config.suffix_strategy,
+        };
+    }
+
+    /// Add a name suffix as configured in `TroveOptions` to `command`, picking another one until the name does not collide
+    ///
+    /// If every random suffix of the configured length is taken, longer suffixes are tried.
+    /// Counter suffixes are the lowest free number of the namespace, starting at 2
+    pub fn with_unique_name_suffix(&self, command: &HoardCmd) -> HoardCmd {
+        self.with_name_suffix_where(command, |c| self.get_command_collision(c).is_none())
+    }
+
+    /// Add a name suffix as configured in `TroveOptions` to `command`, picking another one until `is_free` accepts it
+    fn with_name_suffix_where(
+        &self,
+        command: &HoardCmd,
+        is_free: impl Fn(&HoardCmd) -> bool,
+    ) -> HoardCmd {
+        let numbered = |base: &str| {
+            let mut n = 2;
+            loop {
+                let suffixed = command.clone().with_name(&format!("{base}-{n}"));
+                if is_free(&suffixed) {
+                    return suffixed;
+                }
+                n += 1;
+            }
+        };
+        match self.options.suffix_strategy {
+            SuffixStrategy::Random => {}
+            SuffixStrategy::Counter => return numbered(&command.name),
+            SuffixStrategy::Date => {
+                let dated = format!("{}-{}", command.name, Local::now().format("%Y%m%d"));
+                let suffixed = command.clone().with_name(&dated);
+                return if is_free(&suffixed) {
+                    suffixed
+                } else {
+                    numbered(&dated)
+                };
+            }
+        }
+        let mut attempts = 0;
+        loop {
+            // A suffix is always added, even if the configured length is zero
+            let length = self.options.suffix_length.max(1) + attempts / SUFFIX_ATTEMPTS;
+            let suffixed = command
+                .clone()
+                .with_random_name_suffix_from(length, self.options.suffix_charset.as_deref());
+            if is_free(&suffixed) {
+                return suffixed;
+            }
+            attempts += 1;
+        }
+    }
+
+    /// Check if `a` and `b` are the same namespace, ignoring case if namespaces are not case sensitive
+    fn same_namespace(&self, a: &str, b: &str) -> bool {
+        if self.options.case_sensitive_namespaces {
+            a == b
+        } else {
+            a.to_lowercase() == b.to_lowercase()
         }
     }
 
     /// Loads a local trove file and tries to parse it to load it into memory
+    ///
+    /// If the trove file is invalid, the error is logged and an empty trove is returned.
+    /// Use `try_load_trove_file` to handle invalid trove files
     pub fn load_trove_file(path: &Option<PathBuf>) -> Self {
-        let mut trove = path.clone().map_or_else(
-            || {
-                info!("[DEBUG] No trove path available. Creating new trove file");
-                Self::default()
-            },
-            |p| {
-                if p.exists() {
-                    let f = std::fs::File::open(p).ok().unwrap();
-                    let parsed_trove = serde_yaml::from_reader::<_, Self>(f);
-                    match parsed_trove {
-                        Ok(trove) => trove,
-                        Err(e) => {
-                            eprintln!("The supplied trove file is invalid!");
-                            eprintln!("{e}");
-                            Self::default()
-                        }
-                    }
-                } else {
-                    info!("[DEBUG] No trove file found at {:?}", p);
-                    Self::default()
+        Self::try_load_trove_file(path).unwrap_or_else(|e| {
+            error!("The supplied trove file is invalid! {e}");
+            Self::default()
+        })
+    }
+
+    #[allow(clippy::ref_option)]
+    /// Loads a local trove file and tries to parse it to load it into memory
+    ///
+    /// Returns an empty trove if there is no path or no file at the path,
+    /// and an error if the file can not be read or parsed.
+    /// Files ending with `.json` are parsed as json, all others as yaml
+    pub fn try_load_trove_file(path: &Option<PathBuf>) -> Result<Self, TroveError> {
+        let Some(p) = path else {
+            info!("[DEBUG] No trove path available. Creating new trove file");
+            return Ok(Self::default());
+        };
+        if !p.exists() {
+            info!("[DEBUG] No trove file found at {:?}", p);
+            return Ok(Self::default());
+        }
+        let f = BufReader::new(fs::File::open(p)?);
+        if is_json_path(p) {
+            let mut trove: Self = serde_json::from_reader(f)?;
+            trove.normalize();
+            Ok(trove)
+        } else {
+            Self::load_trove_from_reader(f)
+        }
+    }
+
+    /// Loads a trove collection in yaml format from `r`, e.g. stdin
+    pub fn load_trove_from_reader<R: Read>(r: R) -> Result<Self, TroveError> {
+        let mut trove: Self = serde_yaml::from_reader(r)?;
+        trove.normalize();
+        Ok(trove)
+    }
+
+    #[allow(dead_code)]
+    /// Write the trove collection in yaml format to `w`, e.g. stdout, see `write_yaml`
+    pub fn write_to_writer<W: Write>(&self, mut w: W) -> Result<(), TroveError> {
+        self.write_yaml(&mut w)
+    }
+
+    #[allow(dead_code)]
+    /// Loads the trove file of `config` and merges the `additional_troves` of the config on top of it
+    ///
+    /// The additional troves are overlays and are only read. An additional trove that is missing
+    /// or can not be parsed is skipped with a warning
+    pub fn load_configured(config: &HoardConfig) -> Self {
+        let mut trove = Self::load_trove_file(&config.trove_path);
+        trove.apply_config(config);
+        trove.dedupe_if_configured(config);
+        for path in &config.additional_troves {
+            if !path.exists() {
+                warn!("Skipping additional trove {}: no such file", path.display());
+                continue;
+            }
+            match Self::try_load_trove_file(&Some(path.clone())) {
+                Ok(overlay) => {
+                    trove.merge_trove(&overlay);
                 }
-            },
-        );
-        trove.namespaces = trove.namespaces().into_iter().map(std::string::ToString::to_string).collect();
+                Err(e) => warn!("Skipping additional trove {}: {e}", path.display()),
+            }
+        }
         trove
     }
 
+    #[allow(dead_code)]
+    /// Loads a local trove file, skipping command entries that can not be parsed instead of failing entirely
+    ///
+    /// Returns the trove of all valid commands together with a `TroveError::InvalidCommand` for each skipped entry.
+    /// If the file can not be read or is no valid yaml at all, an empty trove and that error are returned
+    pub fn load_trove_lenient(path: &Path) -> (Self, Vec<TroveError>) {
+        let value: serde_yaml::Value = match fs::File::open(path)
+            .map_err(TroveError::from)
+            .and_then(|f| serde_yaml::from_reader(f).map_err(TroveError::from))
+        {
+            Ok(value) => value,
+            Err(e) => return (Self::default(), vec![e]),
+        };
+        let mut errors = Vec::new();
+        let mut commands = Vec::new();
+        let entries = value
+            .get("commands")
+            .and_then(serde_yaml::Value::as_sequence)
+            .map_or(&[][..], Vec::as_slice);
+        for (index, entry) in entries.iter().enumerate() {
+            match serde_yaml::from_value::<HoardCmd>(entry.clone()) {
+                Ok(mut command) => {
+                    command.mut_dedupe_tags();
+                    commands.push(command);
+                }
+                Err(source) => errors.push(TroveError::InvalidCommand {
+                    index,
+                    name: entry
+                        .get("name")
+                        .and_then(serde_yaml::Value::as_str)
+                        .map(ToString::to_string),
+                    source,
+                }),
+            }
+        }
+        let mut trove = Self::from_commands(&commands);
+        if let Some(version) = value.get("version").and_then(serde_yaml::Value::as_str) {
+            trove.version = version.to_string();
+        }
+        if let Some(aliases) = value
+            .get("namespace_aliases")
+            .and_then(|aliases| serde_yaml::from_value(aliases.clone()).ok())
+        {
+            trove.namespace_aliases = aliases;
+        }
+        (trove, errors)
+    }
+
+    #[allow(dead_code, clippy::ref_option)]
+    /// Loads a local trove file like `try_load_trove_file`, quarantining commands that are not valid
+    ///
+    /// Hand-edited trove files may contain unfinished commands, e.g. with an empty `command`.
+    /// These are removed from the loaded trove and returned separately, so they can be reported or fixed.
+    /// The number of skipped commands is logged as a warning
+    pub fn load_trove_file_validated(
+        path: &Option<PathBuf>,
+    ) -> Result<(Self, Vec<HoardCmd>), TroveError> {
+        let mut trove = Self::try_load_trove_file(path)?;
+        let (valid, invalid): (Vec<HoardCmd>, Vec<HoardCmd>) = std::mem::take(&mut trove.commands)
+            .into_iter()
+            .partition(HoardCmd::is_valid);
+        trove.commands = valid;
+        if !invalid.is_empty() {
+            for command in &invalid {
+                warn!(
+                    "Skipping invalid command '{}/{}': {}",
+                    command.namespace,
+                    command.name,
+                    command.invalid_reason()
+                );
+            }
+            warn!(
+                "Skipped {} invalid commands while loading the trove",
+                invalid.len()
+            );
+            trove.rebuild_namespaces();
+        }
+        Ok((trove, invalid))
+    }
+
     /// Loads a trove collection from a string and tries to parse it to load it into memory
     pub fn load_trove_from_string(trove_string: &str) -> Self {
-        let parsed_trove = serde_yaml::from_str::<Self>(trove_string);
+        Self::from_parsed(serde_yaml::from_str::<Self>(trove_string).map_err(TroveError::from))
+    }
+
+    #[allow(dead_code)]
+    /// Loads a trove collection from a json string and tries to parse it to load it into memory
+    pub fn load_trove_from_json_string(trove_string: &str) -> Self {
+        Self::from_parsed(serde_json::from_str::<Self>(trove_string).map_err(TroveError::from))
+    }
+
+    /// Normalize a parsed trove collection, falling back to an empty trove if it is invalid
+    fn from_parsed(parsed_trove: Result<Self, TroveError>) -> Self {
         let mut trove = match parsed_trove {
             Ok(trove) => trove,
             Err(e) => {
@@ -97,34 +492,296 @@ impl Trove {
                 Self::default()
             }
         };
-        trove.namespaces = trove.namespaces().into_iter().map(std::string::ToString::to_string).collect();
+        trove.normalize();
         trove
     }
 
+    /// Rebuild the namespace set and remove duplicate tags of a loaded trove collection
+    pub fn normalize(&mut self) {
+        self.rebuild_namespaces();
+        for command in &mut self.commands {
+            command.mut_dedupe_tags();
+        }
+    }
+
     /// Serialize trove collection to yaml format and returns it as a string
+    /// Multi-line commands and descriptions are written as literal block scalars to keep them readable
+    ///
+    /// If the trove can not be serialized, the error is logged and an empty string is returned.
+    /// Use `try_to_yaml` to handle the error
     pub fn to_yaml(&self) -> String {
-        serde_yaml::to_string(&self).unwrap()
+        self.to_yaml_opts(YamlStyle::default())
+    }
+
+    #[allow(dead_code)]
+    /// Serialize trove collection to yaml format like `to_yaml`, returning an error if it can not be serialized
+    pub fn try_to_yaml(&self) -> Result<String, TroveError> {
+        self.try_to_yaml_opts(YamlStyle::default())
+    }
+
+    #[allow(dead_code)]
+    /// Serialize trove collection to json format and returns it as a string
+    ///
+    /// If the trove can not be serialized, the error is logged and an empty string is returned
+    pub fn to_json(&self) -> String {
+        self.try_to_json().unwrap_or_else(|e| {
+            error!("Unable to serialize the trove: {e}");
+            String::new()
+        })
+    }
+
+    #[allow(dead_code)]
+    /// Serialize trove collection to json format like `to_json`, returning an error if it can not be serialized
+    pub fn try_to_json(&self) -> Result<String, TroveError> {
+        Ok(serde_json::to_string_pretty(&self)?)
+    }
+
+    /// Serialize trove collection to yaml format formatted with `opts` and returns it as a string
+    /// Formatting applies to commands and descriptions, see `YamlStyle`
+    ///
+    /// If the trove can not be serialized, the error is logged and an empty string is returned
+    pub fn to_yaml_opts(&self, opts: YamlStyle) -> String {
+        self.try_to_yaml_opts(opts).unwrap_or_else(|e| {
+            error!("Unable to serialize the trove: {e}");
+            String::new()
+        })
+    }
+
+    /// Serialize trove collection to yaml format formatted with `opts`, returning an error if it can not be serialized
+    pub fn try_to_yaml_opts(&self, opts: YamlStyle) -> Result<String, TroveError> {
+        Ok(with_style(
+            &serde_yaml::to_string(&self)?,
+            YAML_STYLED_KEYS,
+            opts,
+        ))
+    }
+
+    #[allow(dead_code)]
+    /// Returns the length of the yaml serialized trove collection in bytes
+    pub fn serialized_size(&self) -> usize {
+        self.to_yaml().len()
+    }
+
+    #[allow(dead_code)]
+    /// Check if the yaml serialized trove collection is at most `bytes` long
+    pub fn fits_within(&self, bytes: usize) -> bool {
+        self.serialized_size() <= bytes
+    }
+
+    #[allow(dead_code)]
+    /// Suggest namespaces to split out so the remaining trove collection fits within `bytes`
+    ///
+    /// Namespaces are picked by their serialized size, largest first.
+    /// Returns an empty list if the trove already fits.
+    /// If the trove does not fit even with all namespaces split out, all namespaces are returned
+    pub fn suggest_namespace_splits(&self, bytes: usize) -> Vec<String> {
+        if self.fits_within(bytes) {
+            return Vec::new();
+        }
+        let mut namespace_sizes: Vec<(String, usize)> = self
+            .namespaces()
+            .into_iter()
+            .map(|namespace| {
+                let commands: Vec<&HoardCmd> = self
+                    .commands
+                    .iter()
+                    .filter(|c| c.namespace == namespace)
+                    .collect();
+                let namespace_size = serde_yaml::to_string(&commands).map_or(0, |s| s.len());
+                (namespace.to_string(), namespace_size)
+            })
+            .collect();
+        namespace_sizes.sort_by(|a, b| b.1.cmp(&a.1).then_with(|| a.0.cmp(&b.0)));
+
+        let mut remaining = self.clone();
+        let mut splits = Vec::new();
+        for (namespace, _) in namespace_sizes {
+            if remaining.fits_within(bytes) {
+                break;
+            }
+            remaining.commands.retain(|c| c.namespace != namespace);
+            remaining.namespaces.remove(&namespace);
+            splits.push(namespace);
+        }
+        splits
+    }
+
+    /// Serialize the trove collection to yaml format directly into `w`
+    ///
+    /// The output is the same as `to_yaml`, without holding the whole serialized trove in memory.
+    /// Wrap `w` to e.g. report progress on large troves
+    pub fn write_yaml<W: Write>(&self, w: &mut W) -> Result<(), TroveError> {
+        let mut writer = StyledWriter::new(w, YAML_STYLED_KEYS, YamlStyle::default());
+        serde_yaml::to_writer(&mut writer, self)?;
+        writer.finish()?;
+        Ok(())
     }
 
     /// Save the trove collection to `path` as a yaml file
-    pub fn save_trove_file(&self, path: &Path) {
-        let s = self.to_yaml();
-        fs::write(path, s).expect("Unable to write config file");
+    ///
+    /// The file is replaced atomically, see `write_atomically`, so an interrupted save never leaves a truncated trove file
+    pub fn save_trove_file(&self, path: &Path) -> Result<(), TroveError> {
+        write_atomically(path, |w| self.write_yaml(w))
+    }
+
+    #[allow(dead_code)]
+    /// Save the trove collection to `path`, as a json file if it ends with `.json` and as a yaml file otherwise
+    pub fn save_trove_file_as(&self, path: &Path) -> Result<(), TroveError> {
+        if is_json_path(path) {
+            write_atomically(path, |w| {
+                serde_json::to_writer_pretty(w, self)?;
+                Ok(())
+            })
+        } else {
+            self.save_trove_file(path)
+        }
+    }
+
+    /// Get the position of the command `name` in the trove collection
+    ///
+    /// `name` may be qualified with a namespace like `namespace/name`, see `get_command`.
+    /// Returns `TroveError::CommandNotFound` if there is no such command and `TroveError::AmbiguousCommand`
+    /// if `name` is not qualified and several namespaces have a command with that name
+    pub fn command_position(&self, name: &str) -> Result<usize, TroveError> {
+        if let Some((namespace, command_name)) = name.rsplit_once('/') {
+            let namespace = self.resolve_namespace_alias(namespace);
+            return self
+                .commands
+                .iter()
+                .position(|c| c.namespace == namespace && c.name == command_name)
+                .ok_or_else(|| TroveError::CommandNotFound(name.to_string()));
+        }
+        let positions: Vec<usize> = (0..self.commands.len())
+            .filter(|&i| self.commands[i].name == name)
+            .collect();
+        match positions.as_slice() {
+            [] => Err(TroveError::CommandNotFound(name.to_string())),
+            [position] => Ok(*position),
+            _ => Err(TroveError::AmbiguousCommand {
+                name: name.to_string(),
+                namespaces: positions
+                    .iter()
+                    .map(|&i| self.commands[i].namespace.clone())
+                    .collect(),
+            }),
+        }
+    }
+
+    /// Get a command other than the one at `position` with the same name and namespace as `command`
+    pub fn get_command_collision_except(
+        &self,
+        command: &HoardCmd,
+        position: usize,
+    ) -> Option<&HoardCmd> {
+        self.commands.iter().enumerate().find_map(|(i, c)| {
+            (i != position
+                && c.name == command.name
+                && self.same_namespace(&c.namespace, &command.namespace))
+            .then_some(c)
+        })
     }
 
     /// Given a `HoardCmd`, check if there is a command with the same name and namespace already in the collection
+    /// Namespaces are compared ignoring case if namespaces are not case sensitive, see `TroveOptions`
     /// If there is, return the colliding command
     /// If there is not, return `None`
     pub fn get_command_collision(&self, command: &HoardCmd) -> Option<HoardCmd> {
         let colliding_commands = self
             .commands
             .iter()
-            .filter(|&c| c.namespace == command.namespace)
+            .filter(|&c| self.same_namespace(&c.namespace, &command.namespace))
             .filter(|&c| c.name == command.name)
             .cloned();
         colliding_commands.into_iter().next()
     }
 
+    #[allow(dead_code)]
+    /// Set the listing order of the commands of `namespace` to `ordered_names`
+    ///
+    /// The listed commands get sequential `order` values, all other commands of the namespace
+    /// are ordered after them, keeping their previous order.
+    /// Returns `TroveError::CommandNotFound` if a name is not a command of the namespace, without changing the order
+    pub fn reorder_namespace(
+        &mut self,
+        namespace: &str,
+        ordered_names: &[&str],
+    ) -> Result<(), TroveError> {
+        let namespace = self.resolve_namespace_alias(namespace).to_string();
+        if let Some(missing) = ordered_names.iter().find(|name| {
+            !self
+                .commands
+                .iter()
+                .any(|c| c.namespace == namespace && c.name == **name)
+        }) {
+            return Err(TroveError::CommandNotFound(format!(
+                "{namespace}/{missing}"
+            )));
+        }
+        let mut orders: Vec<(usize, usize)> = Vec::new();
+        let mut unlisted: Vec<usize> = Vec::new();
+        for (position, command) in self
+            .commands
+            .iter()
+            .enumerate()
+            .filter(|(_, c)| c.namespace == namespace)
+        {
+            match ordered_names.iter().position(|name| *name == command.name) {
+                Some(order) => orders.push((position, order)),
+                None => unlisted.push(position),
+            }
+        }
+        unlisted.sort_by_key(|p| self.commands[*p].order.unwrap_or(usize::MAX));
+        orders.extend(
+            unlisted
+                .into_iter()
+                .enumerate()
+                .map(|(offset, position)| (position, ordered_names.len() + offset)),
+        );
+        for (position, order) in orders {
+            if self.commands[position].order != Some(order) {
+                let mut command = self.commands[position].clone();
+                command.order = Some(order);
+                self.replace_at(position, command);
+            }
+        }
+        Ok(())
+    }
+
+    #[allow(dead_code)]
+    /// Get all commands with the commands of each namespace sorted by their `order`, unordered commands last
+    ///
+    /// Namespaces keep the positions their commands have in the trove, see `sorted_commands` to also sort namespaces
+    pub fn ordered_commands(&self) -> Vec<&HoardCmd> {
+        let mut ordered: Vec<&HoardCmd> = self.commands.iter().collect();
+        for namespace in self.namespaces() {
+            let slots: Vec<usize> = (0..ordered.len())
+                .filter(|i| ordered[*i].namespace == namespace)
+                .collect();
+            let mut commands: Vec<&HoardCmd> = slots.iter().map(|i| ordered[*i]).collect();
+            commands.sort_by_key(|c| c.order.unwrap_or(usize::MAX));
+            for (slot, command) in slots.into_iter().zip(commands) {
+                ordered[slot] = command;
+            }
+        }
+        ordered
+    }
+
+    /// Get all commands sorted by namespace, then by name, without changing the trove collection
+    ///
+    /// Within a namespace, commands with a persisted `order` come first, sorted by it.
+    /// This is the order of `print_trove` and of saved trove files
+    pub fn sorted_commands(&self) -> Vec<&HoardCmd> {
+        let mut sorted: Vec<&HoardCmd> = self.commands.iter().collect();
+        sorted.sort_by(|a, b| listing_order(a, b));
+        sorted
+    }
+
+    #[allow(dead_code)]
+    /// Sort the commands of the trove collection in place, in the order of `sorted_commands`
+    pub fn sort(&mut self) {
+        self.commands.sort_by(listing_order);
+    }
+
     /// Get all commands in the trove collection sorted by usage count
     pub fn get_commands_sorted_by_usage(&self) -> Vec<HoardCmd> {
         let mut commands = self.commands.clone();
@@ -132,17 +789,146 @@ impl Trove {
         commands
     }
 
+    #[allow(dead_code)]
+    /// Get the `n` most used commands, most used first
+    ///
+    /// Commands used equally often are sorted by name
+    pub fn most_used(&self, n: usize) -> Vec<&HoardCmd> {
+        let mut commands: Vec<&HoardCmd> = self.commands.iter().collect();
+        commands.sort_by(|a, b| {
+            b.usage_count
+                .cmp(&a.usage_count)
+                .then_with(|| a.name.cmp(&b.name))
+        });
+        commands.truncate(n);
+        commands
+    }
+
+    #[allow(dead_code)]
+    /// Record that the command `name` was used, increasing its usage count and updating its last usage
+    ///
+    /// `name` may be qualified with a namespace like `namespace/name`, see `get_command`.
+    /// Returns `Err(anyhow::Error)` if no command with that name is in the trove
+    pub fn record_usage(&mut self, name: &str) -> Result<(), anyhow::Error> {
+        let position = match name.rsplit_once('/') {
+            Some((namespace, command_name)) => {
+                let namespace = self.resolve_namespace_alias(namespace).to_string();
+                self.commands
+                    .iter()
+                    .position(|c| c.namespace == namespace && c.name == command_name)
+            }
+            None => self.commands.iter().position(|c| c.name == name),
+        };
+        let position = position.ok_or_else(|| anyhow!("Command not found [{}]", name))?;
+        let mut command = self.commands[position].clone();
+        command.mut_increase_usage_count();
+        command.mut_update_last_used();
+        self.replace_at(position, command);
+        Ok(())
+    }
+
+    #[allow(dead_code)]
+    /// Get all commands tagged with `tag`, ignoring surrounding whitespace of tags
+    pub fn commands_with_tag(&self, tag: &str) -> Vec<&HoardCmd> {
+        let tag = tag.trim();
+        self.commands
+            .iter()
+            .filter(|c| c.tags.iter().any(|t| t.trim() == tag))
+            .collect()
+    }
+
+    #[allow(dead_code)]
+    /// Returns all tags in use together with the number of commands tagged with them
+    ///
+    /// Tags only differing in case are counted as one, listed in the casing they are first seen with.
+    /// Sorted by descending count with ties sorted by tag, ignoring case
+    pub fn all_tags(&self) -> Vec<(String, usize)> {
+        let mut tags: Vec<(String, usize)> = Vec::new();
+        let mut positions: HashMap<String, usize> = HashMap::new();
+        for command in &self.commands {
+            let mut seen: HashSet<String> = HashSet::new();
+            for tag in command
+                .tags
+                .iter()
+                .map(|t| t.trim())
+                .filter(|t| !t.is_empty())
+            {
+                let key = tag.to_lowercase();
+                if !seen.insert(key.clone()) {
+                    continue;
+                }
+                if let Some(&position) = positions.get(&key) {
+                    tags[position].1 += 1;
+                } else {
+                    positions.insert(key, tags.len());
+                    tags.push((tag.to_string(), 1));
+                }
+            }
+        }
+        tags.sort_by(|a, b| {
+            b.1.cmp(&a.1)
+                .then_with(|| a.0.to_lowercase().cmp(&b.0.to_lowercase()))
+        });
+        tags
+    }
+
+    #[allow(dead_code)]
+    /// Get all commands tagged with all of `tags` if `match_all` is set, or with any of them otherwise
+    ///
+    /// Tags are compared ignoring case and surrounding whitespace. An empty `tags` slice returns all commands
+    pub fn filter_by_tags(&self, tags: &[String], match_all: bool) -> Vec<&HoardCmd> {
+        if tags.is_empty() {
+            return self.commands.iter().collect();
+        }
+        let wanted: Vec<String> = tags.iter().map(|t| t.trim().to_lowercase()).collect();
+        self.commands
+            .iter()
+            .filter(|c| {
+                let has_tag = |tag: &String| c.tags.iter().any(|t| t.trim().to_lowercase() == *tag);
+                if match_all {
+                    wanted.iter().all(has_tag)
+                } else {
+                    wanted.iter().any(has_tag)
+                }
+            })
+            .collect()
+    }
+
+    #[allow(dead_code)]
+    /// Get all commands created at or after `since`, e.g. to audit recently added commands
+    pub fn commands_created_since(&self, since: DateTime<Utc>) -> Vec<&HoardCmd> {
+        let since = std::time::SystemTime::from(since);
+        self.commands
+            .iter()
+            .filter(|c| c.created >= since)
+            .collect()
+    }
+
+    #[allow(dead_code)]
+    /// Get all commands without any tag, tags that are empty or only whitespace do not count
+    pub fn untagged_commands(&self) -> Vec<&HoardCmd> {
+        self.commands
+            .iter()
+            .filter(|c| c.tags.iter().all(|t| t.trim().is_empty()))
+            .collect()
+    }
+
     /// Given a `HoardCmd`, check if there is a command with the same name, namespace and saved command already in the collection.
     /// A command with those same parameters is considered to be the same command
+    /// If `normalize` is set, saved commands only differing in formatting are the same, see `HoardCmd::normalized_command`
     /// If there is, return `true`
     /// If there is not, return `false`
-    fn is_command_present(&self, command: &HoardCmd) -> bool {
+    fn is_command_present(&self, command: &HoardCmd, normalize: bool) -> bool {
         self.commands
             .iter()
             .filter(|&c| {
                 c.namespace == command.namespace
                     && c.name == command.name
-                    && c.command == command.command
+                    && if normalize {
+                        c.normalized_command() == command.normalized_command()
+                    } else {
+                        c.command == command.command
+                    }
             })
             .count()
             > 0
@@ -163,20 +949,46 @@ impl Trove {
         overwrite_colliding: bool,
     ) -> Result<bool, HoardErr> {
         if !new_command.is_valid() {
-            return Err(HoardErr::new("cannot save invalid command"));
+            return Err(HoardErr::new(&format!(
+                "cannot save invalid command: {}",
+                new_command.invalid_reason()
+            )));
+        }
+        let mut new_command = if new_command.id.is_empty() {
+            new_command.with_id(&generate_id())
+        } else {
+            new_command
+        };
+        new_command.mut_dedupe_tags();
+        // Keep the spelling of an existing namespace if namespaces are not case sensitive
+        if let Some(namespace) = self
+            .namespaces()
+            .into_iter()
+            .find(|n| self.same_namespace(n, &new_command.namespace))
+        {
+            new_command.namespace = namespace.to_string();
         }
         let dirty = match self.get_command_collision(&new_command) {
             // Collision is present, but its the same command, do nothing
-            Some(_) if self.is_command_present(&new_command) => false,
+            Some(_) if self.is_command_present(&new_command, false) => false,
             // collision is present, overwrite_colliding is true, resolve collision by overwriting
             Some(colliding_command) if overwrite_colliding => {
                 self.commands.retain(|x| x != &colliding_command);
+                self.emit(|| TroveEvent::Updated {
+                    namespace: colliding_command.namespace.clone(),
+                    name: colliding_command.name.clone(),
+                    command: new_command.clone(),
+                });
                 self.commands.push(new_command);
                 true
             }
             // collision is present, but overwrite_colliding is false, add random suffix before adding as a new comamnd
+            // The suffixed command is added next to the colliding one, so it must not share its id
             Some(_) => {
-                let c = new_command.with_random_name_suffix();
+                let c = self
+                    .with_unique_name_suffix(&new_command)
+                    .with_id(&generate_id());
+                self.emit(|| TroveEvent::Added { command: c.clone() });
                 self.commands.push(c);
                 true
             }
@@ -184,6 +996,9 @@ impl Trove {
             None => {
                 // no collision, maybe add the namespace
                 self.add_namespace(&new_command.namespace);
+                self.emit(|| TroveEvent::Added {
+                    command: new_command.clone(),
+                });
                 self.commands.push(new_command);
                 true
             }
@@ -191,9 +1006,147 @@ impl Trove {
         Ok(dirty)
     }
 
+    #[allow(dead_code)]
+    /// Adds many commands to the trove collection like calling `add_command` for each of them, reporting the changes
+    ///
+    /// Existing names are indexed once, so adding many commands takes linear instead of quadratic time.
+    /// Commands identical to an existing command are skipped. On a name collision, the existing command is
+    /// overwritten if `overwrite_colliding` is set, otherwise the added command gets a random name suffix.
+    /// Returns `TroveError::Invalid` without changing the trove collection if any command is not valid
+    pub fn add_commands(
+        &mut self,
+        new_commands: Vec<HoardCmd>,
+        overwrite_colliding: bool,
+    ) -> Result<MergeReport, TroveError> {
+        if let Some(invalid) = new_commands.iter().find(|c| !c.is_valid()) {
+            return Err(TroveError::Invalid(format!(
+                "{}/{}: {}",
+                invalid.namespace,
+                invalid.name,
+                invalid.invalid_reason()
+            )));
+        }
+        let case_sensitive = self.options.case_sensitive_namespaces;
+        let key = |namespace: &str, name: &str| {
+            let namespace = if case_sensitive {
+                namespace.to_string()
+            } else {
+                namespace.to_lowercase()
+            };
+            (namespace, name.to_string())
+        };
+        let mut positions: HashMap<(String, String), usize> = HashMap::new();
+        for (position, command) in self.commands.iter().enumerate() {
+            positions
+                .entry(key(&command.namespace, &command.name))
+                .or_insert(position);
+        }
+        // Keep the spelling of an existing namespace if namespaces are not case sensitive
+        let mut spellings: HashMap<String, String> = HashMap::new();
+        for command in &self.commands {
+            spellings
+                .entry(key(&command.namespace, "").0)
+                .or_insert_with(|| command.namespace.clone());
+        }
+
+        let mut report = MergeReport::default();
+        for new_command in new_commands {
+            let mut new_command = if new_command.id.is_empty() {
+                new_command.with_id(&generate_id())
+            } else {
+                new_command
+            };
+            new_command.mut_dedupe_tags();
+            let namespace = spellings
+                .entry(key(&new_command.namespace, "").0)
+                .or_insert_with(|| new_command.namespace.clone());
+            new_command.namespace.clone_from(namespace);
+            let qualified_name = format!("{}/{}", new_command.namespace, new_command.name);
+            match positions.get(&key(&new_command.namespace, &new_command.name)) {
+                Some(&position)
+                    if self.commands[position].namespace == new_command.namespace
+                        && self.commands[position].command == new_command.command =>
+                {
+                    report.skipped.push(qualified_name);
+                }
+                Some(&position) if overwrite_colliding => {
+                    let colliding = &self.commands[position];
+                    self.emit(|| TroveEvent::Updated {
+                        namespace: colliding.namespace.clone(),
+                        name: colliding.name.clone(),
+                        command: new_command.clone(),
+                    });
+                    self.commands[position] = new_command;
+                    report.overwritten.push(qualified_name);
+                }
+                Some(_) => {
+                    let suffixed = self
+                        .with_name_suffix_where(&new_command, |c| {
+                            !positions.contains_key(&key(&c.namespace, &c.name))
+                        })
+                        .with_id(&generate_id());
+                    report.renamed.push((
+                        qualified_name,
+                        format!("{}/{}", suffixed.namespace, suffixed.name),
+                    ));
+                    self.emit(|| TroveEvent::Added {
+                        command: suffixed.clone(),
+                    });
+                    positions.insert(
+                        key(&suffixed.namespace, &suffixed.name),
+                        self.commands.len(),
+                    );
+                    self.commands.push(suffixed);
+                }
+                None => {
+                    self.add_namespace(&new_command.namespace);
+                    self.emit(|| TroveEvent::Added {
+                        command: new_command.clone(),
+                    });
+                    positions.insert(
+                        key(&new_command.namespace, &new_command.name),
+                        self.commands.len(),
+                    );
+                    self.commands.push(new_command);
+                    report.added.push(qualified_name);
+                }
+            }
+        }
+        report.changed = !(report.added.is_empty()
+            && report.overwritten.is_empty()
+            && report.renamed.is_empty());
+        Ok(report)
+    }
+
+    #[allow(dead_code)]
+    /// Adds a command to the trove collection, failing instead of renaming it on a name collision
+    ///
+    /// Returns `TroveError::CommandCollision` with the existing command if the name is already taken in the namespace,
+    /// also if both commands are the same, and `TroveError::Invalid` if the command is not valid.
+    /// The trove collection is not changed if an error is returned
+    pub fn add_command_strict(&mut self, new_command: HoardCmd) -> Result<(), TroveError> {
+        if !new_command.is_valid() {
+            return Err(TroveError::Invalid(new_command.invalid_reason()));
+        }
+        if let Some(existing) = self.get_command_collision(&new_command) {
+            return Err(TroveError::CommandCollision {
+                namespace: existing.namespace,
+                name: existing.name,
+                command: existing.command,
+            });
+        }
+        self.add_command(new_command, false)
+            .map(|_| ())
+            .map_err(|e| TroveError::Invalid(e.to_string()))
+    }
+
     /// try to add a namespace value to the namespaces if it is not present yet
     pub fn add_namespace(&mut self, namespace: &str) {
-        if !self.namespaces.contains(namespace) {
+        if !self
+            .namespaces
+            .iter()
+            .any(|n| self.same_namespace(n, namespace))
+        {
             self.namespaces.insert(namespace.to_string());
         }
     }
@@ -208,7 +1161,93 @@ impl Trove {
         if command_position.is_none() {
             return Err(anyhow!("Command not found [{}]", name));
         }
+        self.emit_removed(|x| x.name == name);
         self.commands.retain(|x| &*x.name != name);
+        self.prune_empty_namespaces();
+        Ok(())
+    }
+
+    #[allow(dead_code)]
+    /// Remove all commands tagged with `tag` from the trove collection, ignoring surrounding whitespace of tags
+    ///
+    /// Namespaces without commands after the removal are dropped from the namespace set.
+    /// Returns the number of removed commands
+    ///
+    /// Returns `Err(anyhow::Error)` if no command has the tag
+    pub fn remove_commands_by_tag(&mut self, tag: &str) -> Result<usize, anyhow::Error> {
+        let before = self.commands.len();
+        let tagged = |c: &HoardCmd| c.tags.iter().any(|t| t.trim() == tag.trim());
+        self.emit_removed(tagged);
+        self.commands.retain(|c| !tagged(c));
+        let removed = before - self.commands.len();
+        if removed == 0 {
+            return Err(anyhow!("No commands found with tag [{}]", tag));
+        }
+        self.prune_empty_namespaces();
+        Ok(removed)
+    }
+
+    /// Emit a `TroveEvent::Removed` for every command matching `removed`, before removing them
+    fn emit_removed(&self, removed: impl Fn(&HoardCmd) -> bool) {
+        for c in self.commands.iter().filter(|c| removed(c)) {
+            self.emit(|| TroveEvent::Removed {
+                namespace: c.namespace.clone(),
+                name: c.name.clone(),
+            });
+        }
+    }
+
+    /// Replace the command at `position` with `command`, emitting a `TroveEvent::Updated` for the replaced command
+    pub fn replace_at(&mut self, position: usize, command: HoardCmd) {
+        let replaced = &self.commands[position];
+        self.emit(|| TroveEvent::Updated {
+            namespace: replaced.namespace.clone(),
+            name: replaced.name.clone(),
+            command: command.clone(),
+        });
+        self.commands[position] = command;
+    }
+
+    /// Drop all namespaces without commands from the namespace set
+    fn prune_empty_namespaces(&mut self) {
+        let used: HashSet<&str> = self.commands.iter().map(|c| c.namespace.as_str()).collect();
+        self.namespaces.retain(|n| used.contains(n.as_str()));
+    }
+
+    #[allow(dead_code)]
+    /// Get a command of the trove collection by its stable `id`
+    pub fn get_command_by_id(&self, id: &str) -> Option<&HoardCmd> {
+        self.commands.iter().find(|c| c.id == id)
+    }
+
+    #[allow(dead_code)]
+    /// Replace the command with the same `id` as `command`
+    ///
+    /// Returns `Err(anyhow::Error)` if no command with that id is in the trove
+    pub fn update_command_by_id(&mut self, command: &HoardCmd) -> Result<(), anyhow::Error> {
+        let Some(position) = self.commands.iter().position(|x| x.id == command.id) else {
+            return Err(anyhow!("Command not found [{}]", command.id));
+        };
+        self.add_namespace(&command.namespace);
+        let mut updated_command = command.clone();
+        updated_command.mut_dedupe_tags();
+        updated_command.mut_update_last_used();
+        self.replace_at(position, updated_command);
+        Ok(())
+    }
+
+    #[allow(dead_code)]
+    /// Remove a command from the trove collection by its stable `id`
+    ///
+    /// Returns `Err(anyhow::Error)` if no command with that id is in the trove
+    pub fn remove_command_by_id(&mut self, id: &str) -> Result<(), anyhow::Error> {
+        let command_position = self.commands.iter().position(|x| x.id == id);
+        if command_position.is_none() {
+            return Err(anyhow!("Command not found [{}]", id));
+        }
+        self.emit_removed(|x| x.id == id);
+        self.commands.retain(|x| x.id != id);
+        self.prune_empty_namespaces();
         Ok(())
     }
 
@@ -218,84 +1257,636 @@ impl Trove {
             return Err(anyhow!("Command not found [{}]", command.name));
         }
         let mut updated_command = command.clone();
+        updated_command.mut_dedupe_tags();
         updated_command.mut_increase_usage_count();
         updated_command.mut_update_last_used();
-        self.commands[command_position.unwrap()] = updated_command;
+        self.replace_at(command_position.unwrap(), updated_command);
         Ok(())
     }
 
     pub fn remove_namespace_commands(&mut self, namespace: &str) -> Result<(), anyhow::Error> {
-        let command_position = self.commands.iter().position(|x| x.namespace == namespace);
-        if command_position.is_none() {
+        let namespace = self.resolve_namespace_alias(namespace).to_string();
+        if !self.has_namespace(&namespace) {
             return Err(anyhow!("No Commands found in namespace [{}]", namespace));
         }
-        self.commands.retain(|x| &*x.namespace != namespace);
+        self.emit_removed(|x| x.namespace == namespace);
+        self.commands.retain(|x| x.namespace != namespace);
+        self.prune_empty_namespaces();
         Ok(())
     }
 
-    pub fn namespaces(&self) -> Vec<&str> {
-        // Returns all namespaces in the trove
-        let mut namespaces: Vec<_> = self
-            .commands
-            .iter()
-            .map(|command| command.namespace.as_str())
-            .collect::<HashSet<_>>()
-            .into_iter()
-            .collect();
+    #[allow(dead_code)]
+    /// Register `alias` as an alternative name for `namespace`
+    ///
+    /// Returns `Err(anyhow::Error)` if `alias` is the name of an existing namespace
+    pub fn set_namespace_alias(
+        &mut self,
+        alias: &str,
+        namespace: &str,
+    ) -> Result<(), anyhow::Error> {
+        if alias == namespace || self.namespaces.contains(alias) || self.has_namespace(alias) {
+            return Err(anyhow!(
+                "Alias [{}] collides with an existing namespace",
+                alias
+            ));
+        }
+        self.namespace_aliases
+            .insert(alias.to_string(), namespace.to_string());
+        Ok(())
+    }
 
-        namespaces.sort_unstable();
-        namespaces
+    #[allow(dead_code)]
+    /// Resolve user input to a namespace, expanding the `namespace_aliases` of `config` and of the trove
+    ///
+    /// Aliases of the config take precedence over aliases stored in the trove. An alias of the config
+    /// pointing to a namespace without commands is not expanded, so `input` is returned as is.
+    /// The result can be passed to namespace methods like `commands_in_namespace` or `rename_namespace`
+    pub fn resolve_namespace(&self, input: &str, config: &HoardConfig) -> String {
+        if let Some(target) = config.namespace_aliases.get(input) {
+            let target = self.resolve_namespace_alias(target);
+            if self.has_namespace(target) {
+                return target.to_string();
+            }
+            warn!("Namespace alias '{input}' points to unknown namespace '{target}'");
+            return input.to_string();
+        }
+        self.resolve_namespace_alias(input).to_string()
     }
 
-    pub fn pick_command(&self, config: &HoardConfig, name: &str) -> Result<HoardCmd> {
-        let filtered_command: Option<&HoardCmd> = self.commands.iter().find(|c| c.name == name);
+    /// Resolve a namespace alias to its canonical namespace
+    ///
+    /// Returns `namespace` unchanged if it is not an alias
+    pub fn resolve_namespace_alias<'a>(&'a self, namespace: &'a str) -> &'a str {
+        self.namespace_aliases
+            .get(namespace)
+            .map_or(namespace, String::as_str)
+    }
+
+    pub fn namespaces(&self) -> Vec<&str> {
+        // Returns all namespaces in the trove, namespaces only differing in case are listed once if they are the same
+        let mut namespaces: Vec<&str> = Vec::new();
+        for command in &self.commands {
+            if !namespaces
+                .iter()
+                .any(|n| self.same_namespace(n, &command.namespace))
+            {
+                namespaces.push(&command.namespace);
+            }
+        }
+
+        namespaces.sort_unstable();
+        namespaces
+    }
+
+    /// Check if any command of the trove collection is in `namespace`
+    ///
+    /// Consistent with `namespaces`, the possibly stale namespace set and namespace aliases are not considered,
+    /// and the case of `namespace` is ignored if namespaces are not case sensitive
+    pub fn has_namespace(&self, namespace: &str) -> bool {
+        self.commands
+            .iter()
+            .any(|c| self.same_namespace(&c.namespace, namespace))
+    }
+
+    /// Get all commands of `namespace` in listing order, see `sorted_commands`
+    ///
+    /// Namespace aliases are resolved
+    pub fn commands_in_namespace(&self, namespace: &str) -> Vec<&HoardCmd> {
+        let namespace = self.resolve_namespace_alias(namespace);
+        self.sorted_commands()
+            .into_iter()
+            .filter(|c| self.same_namespace(&c.namespace, namespace))
+            .collect()
+    }
+
+    #[allow(dead_code)]
+    /// Check every command of the trove collection and collect all violations in one pass
+    ///
+    /// A command violates the trove if it is not valid (see `HoardCmd::is_valid`),
+    /// if an earlier command has the same name and namespace, or if its namespace is missing from `namespaces`
+    pub fn validate(&self) -> Result<(), Vec<TroveValidationError>> {
+        let mut errors = Vec::new();
+        let mut push = |index: usize, reason: String| {
+            errors.push(TroveValidationError { index, reason });
+        };
+        for (index, command) in self.commands.iter().enumerate() {
+            if !command.is_valid() {
+                push(index, command.invalid_reason());
+            }
+            if let Some(first) = self.commands[..index].iter().position(|c| {
+                c.name == command.name && self.same_namespace(&c.namespace, &command.namespace)
+            }) {
+                push(
+                    index,
+                    format!(
+                        "'{}' in namespace '{}' is a duplicate of command {first}",
+                        command.name, command.namespace
+                    ),
+                );
+            }
+            if !self
+                .namespaces
+                .iter()
+                .any(|ns| self.same_namespace(ns, &command.namespace))
+            {
+                push(
+                    index,
+                    format!(
+                        "namespace '{}' is not in the namespace list",
+                        command.namespace
+                    ),
+                );
+            }
+        }
+        if errors.is_empty() {
+            Ok(())
+        } else {
+            Err(errors)
+        }
+    }
+
+    #[allow(dead_code)]
+    /// Returns all namespaces in the trove together with their number of commands
+    ///
+    /// Sorted by namespace name, or if `by_count` is set, by descending command count with ties sorted by name
+    pub fn namespaces_with_counts(&self, by_count: bool) -> Vec<(String, usize)> {
+        let mut counts: HashMap<&str, usize> = HashMap::new();
+        for command in &self.commands {
+            *counts.entry(command.namespace.as_str()).or_insert(0) += 1;
+        }
+        let mut namespaces: Vec<(String, usize)> = counts
+            .into_iter()
+            .map(|(namespace, count)| (namespace.to_string(), count))
+            .collect();
+        if by_count {
+            namespaces.sort_unstable_by(|a, b| b.1.cmp(&a.1).then_with(|| a.0.cmp(&b.0)));
+        } else {
+            namespaces.sort_unstable();
+        }
+        namespaces
+    }
+
+    #[allow(dead_code)]
+    /// Returns all namespaces with their number of commands, sorted by descending count with ties sorted by name
+    ///
+    /// Counted from the commands, so the result is accurate even if the namespace set is stale
+    pub fn namespace_stats(&self) -> Vec<(String, usize)> {
+        self.namespaces_with_counts(true)
+    }
+
+    #[allow(dead_code)]
+    /// Returns the number of commands in the trove collection
+    pub const fn total_commands(&self) -> usize {
+        self.commands.len()
+    }
+
+    #[allow(dead_code)]
+    /// Get a command by its name, optionally qualified with its namespace as `namespace/name`
+    ///
+    /// The namespace is split off at the last `/` and may be a namespace alias.
+    /// For an unqualified name, the first command with that name is returned
+    pub fn get_command(&self, qualified_name: &str) -> Option<&HoardCmd> {
+        match qualified_name.rsplit_once('/') {
+            Some((namespace, name)) => {
+                let namespace = self.resolve_namespace_alias(namespace);
+                self.commands
+                    .iter()
+                    .find(|c| c.namespace == namespace && c.name == name)
+            }
+            None => self.commands.iter().find(|c| c.name == qualified_name),
+        }
+    }
+
+    #[allow(dead_code)]
+    /// Find a command by its (qualified) name and fill out its parameters from `values` without prompting
+    ///
+    /// Parameters missing from `values` fall back to their declared default value. See `Trove::get_command`.
+    /// The parameter tokens of the command take precedence over the tokens of `config`
+    /// Returns `TroveError::CommandNotFound` if there is no such command
+    /// and `TroveError::MissingParameters` if a parameter has neither a value nor a default
+    pub fn resolve(
+        &self,
+        qualified_name: &str,
+        values: &HashMap<String, String>,
+        config: &HoardConfig,
+    ) -> Result<String, TroveError> {
+        let command = self
+            .get_command(qualified_name)
+            .ok_or_else(|| TroveError::CommandNotFound(qualified_name.to_string()))?;
+        let (token, ending_token) = command.parameter_tokens(config);
+        command.resolve_parameters(&token, &ending_token, values)
+    }
+
+    /// Replace the namespace set with exactly the namespaces referenced by the commands of the trove
+    ///
+    /// Drops namespaces without commands and adds namespaces missing from the set, e.g. after hand-editing the trove file
+    pub fn rebuild_namespaces(&mut self) {
+        self.namespaces = self.commands.iter().map(|c| c.namespace.clone()).collect();
+    }
+
+    #[allow(dead_code)]
+    /// Move all commands of `namespace` matching `pred` to the namespace `into`
+    ///
+    /// Both namespaces may be namespace aliases. A moved command whose name is already taken in `into`
+    /// gets a random name suffix, like colliding commands do when added.
+    /// Returns the number of moved commands
+    pub fn split_namespace(
+        &mut self,
+        namespace: &str,
+        into: &str,
+        pred: impl Fn(&HoardCmd) -> bool,
+    ) -> usize {
+        let namespace = self.resolve_namespace_alias(namespace).to_string();
+        let into = self.resolve_namespace_alias(into).to_string();
+        if namespace == into {
+            return 0;
+        }
+        let positions: Vec<usize> = self
+            .commands
+            .iter()
+            .enumerate()
+            .filter(|(_, c)| c.namespace == namespace && pred(c))
+            .map(|(i, _)| i)
+            .collect();
+        for &position in &positions {
+            let mut moved = self.commands[position].clone().with_namespace(&into);
+            if self.get_command_collision(&moved).is_some() {
+                moved = self.with_unique_name_suffix(&moved);
+            }
+            self.replace_at(position, moved);
+        }
+        if !positions.is_empty() {
+            self.add_namespace(&into);
+        }
+        positions.len()
+    }
+
+    #[allow(dead_code)]
+    /// Move all commands of namespace `old` to namespace `new` and remove `old`
+    ///
+    /// If `new` already exists, the commands are merged into it, like `split_namespace` does.
+    /// Namespace aliases of `old` are pointed to `new`. Returns the number of moved commands
+    ///
+    /// Emits a `TroveEvent::Updated` for every moved command, with the name suffix it may have got, then a `TroveEvent::NamespaceRenamed`
+    pub fn rename_namespace(&mut self, old: &str, new: &str) -> Result<usize, anyhow::Error> {
+        let old = self.resolve_namespace_alias(old).to_string();
+        if !self.has_namespace(&old) {
+            return Err(anyhow!("No Commands found in namespace [{}]", old));
+        }
+        let moved = self.split_namespace(&old, new, |_| true);
+        let new = self.resolve_namespace_alias(new).to_string();
+        self.emit(|| TroveEvent::NamespaceRenamed {
+            old: old.clone(),
+            new: new.clone(),
+        });
+        for target in self.namespace_aliases.values_mut() {
+            if *target == old {
+                target.clone_from(&new);
+            }
+        }
+        self.prune_empty_namespaces();
+        Ok(moved)
+    }
+
+    #[allow(dead_code)]
+    /// Returns all entries of the namespace set that no command references, sorted
+    pub fn unused_namespaces(&self) -> Vec<&str> {
+        let mut unused: Vec<&str> = self
+            .namespaces
+            .iter()
+            .filter(|namespace| !self.commands.iter().any(|c| &c.namespace == *namespace))
+            .map(String::as_str)
+            .collect();
+        unused.sort_unstable();
+        unused
+    }
+
+    #[allow(dead_code)]
+    /// Rewrite all namespace hierarchy separators (`.` and `/`) of the trove collection to `to`
+    ///
+    /// Updates the namespaces of all commands, the namespace set and the targets of namespace aliases.
+    /// Returns the number of commands whose namespace changed
+    pub fn normalize_separators(&mut self, to: char) -> usize {
+        let normalize = |namespace: &str| -> String {
+            namespace
+                .chars()
+                .map(|c| {
+                    if NAMESPACE_SEPARATORS.contains(&c) {
+                        to
+                    } else {
+                        c
+                    }
+                })
+                .collect()
+        };
+        let mut changed = 0;
+        for position in 0..self.commands.len() {
+            let namespace = normalize(&self.commands[position].namespace);
+            if namespace != self.commands[position].namespace {
+                let command = self.commands[position].clone().with_namespace(&namespace);
+                self.replace_at(position, command);
+                changed += 1;
+            }
+        }
+        self.namespaces = self.namespaces.iter().map(|n| normalize(n)).collect();
+        for target in self.namespace_aliases.values_mut() {
+            *target = normalize(target);
+        }
+        changed
+    }
+
+    pub fn pick_command(&self, config: &HoardConfig, name: &str) -> Result<HoardCmd> {
+        let filtered_command: Option<&HoardCmd> = self.commands.iter().find(|c| c.name == name);
         filtered_command.map_or_else(
             || Err(anyhow!("No matching command found with name: {}", name)),
             |command| {
-                let command = command.clone().with_input_parameters(
-                    &config.parameter_token.clone().unwrap(),
-                    &config.parameter_ending_token.clone().unwrap(),
-                );
+                let (token, ending_token) = command.parameter_tokens(config);
+                // Prompting for an unterminated parameter would never finish
+                command.parameters(&token, &ending_token)?;
+                let command = command.clone().with_input_parameters(&token, &ending_token);
                 Ok(command)
             },
         )
     }
 
+    #[allow(dead_code)]
+    /// Replace the command with the same name as `command`, like `update_command_by_name`, failing instead of doing nothing
+    ///
+    /// If several namespaces have a command with that name, the one in the namespace of `command` is replaced.
+    /// Returns `TroveError::CommandNotFound` if no command has that name, `TroveError::AmbiguousCommand` if the command to
+    /// replace is not clear and `TroveError::CommandCollision` if `command` moves to a namespace that already has a command with that name
+    pub fn try_update_command_by_name(&mut self, command: &HoardCmd) -> Result<(), TroveError> {
+        let position = self
+            .commands
+            .iter()
+            .position(|c| c.name == command.name && c.namespace == command.namespace)
+            .map_or_else(|| self.command_position(&command.name), Ok)?;
+        if let Some(existing) = self.get_command_collision_except(command, position) {
+            return Err(TroveError::CommandCollision {
+                namespace: existing.namespace.clone(),
+                name: existing.name.clone(),
+                command: existing.command.clone(),
+            });
+        }
+        let original = &self.commands[position];
+        self.emit(|| TroveEvent::Updated {
+            namespace: original.namespace.clone(),
+            name: original.name.clone(),
+            command: command.clone(),
+        });
+        let moved = original.namespace != command.namespace;
+        self.commands[position] = command.clone();
+        self.commands[position].mut_dedupe_tags();
+        self.commands[position].mut_update_last_used();
+        if moved {
+            self.rebuild_namespaces();
+        }
+        Ok(())
+    }
+
     pub fn update_command_by_name(&mut self, command: &HoardCmd) -> &mut Self {
-        for c in &mut self.commands.iter_mut() {
-            if c.name == command.name {
-                *c = command.clone();
-                c.mut_update_last_used();
+        for position in 0..self.commands.len() {
+            if self.commands[position].name == command.name {
+                let mut updated_command = command.clone();
+                updated_command.mut_update_last_used();
+                self.replace_at(position, updated_command);
             }
         }
         self
     }
 
+    #[allow(dead_code)]
+    /// Run multiple mutations of the trove collection with all-or-nothing semantics
+    ///
+    /// A snapshot of the trove is taken before `f` runs. If `f` returns an error, the trove is restored to that state
+    pub fn transaction<T>(
+        &mut self,
+        f: impl FnOnce(&mut Self) -> Result<T, TroveError>,
+    ) -> Result<T, TroveError> {
+        let snapshot = self.snapshot();
+        let result = f(self);
+        if result.is_err() {
+            self.restore(snapshot);
+        }
+        result
+    }
+
+    /// Take a checkpoint of the trove collection to `restore` it later, e.g. before speculative edits
+    pub fn snapshot(&self) -> TroveSnapshot {
+        TroveSnapshot {
+            trove: self.clone(),
+        }
+    }
+
+    /// Reset the trove collection to the state of `snapshot`
+    pub fn restore(&mut self, snapshot: TroveSnapshot) {
+        *self = snapshot.trove;
+    }
+
     /// check if the trove collection is empty
     pub fn is_empty(&self) -> bool {
         self.commands.is_empty()
     }
 
     pub fn merge_trove(&mut self, other: &Self) -> bool {
-        other
-            .commands
-            .iter()
-            .map(|c| self.add_command(c.clone(), true))
-            .any(|x| x.is_ok())
+        self.merge_trove_with_progress(other, None)
+    }
+
+    /// Merge all commands of `other` into the trove collection. Colliding commands are overwritten.
+    /// Tombstones are merged like other commands, use `apply_patch` to apply them
+    ///
+    /// If `progress` is set, it is called with `(done, total)` every `PROGRESS_INTERVAL` commands and once all commands are merged
+    ///
+    /// Returns `true` if the trove changed
+    pub fn merge_trove_with_progress(
+        &mut self,
+        other: &Self,
+        mut progress: Option<&mut dyn FnMut(usize, usize)>,
+    ) -> bool {
+        let total = other.commands.len();
+        let mut dirty = false;
+        for (i, c) in other.commands.iter().enumerate() {
+            dirty |= matches!(self.add_command(c.clone(), true), Ok(true));
+            let done = i + 1;
+            if let Some(report) = progress.as_mut() {
+                if done % PROGRESS_INTERVAL == 0 || done == total {
+                    report(done, total);
+                }
+            }
+        }
+        dirty
+    }
+
+    /// Remove the command with the same name and namespace as the `tombstone`
+    ///
+    /// Returns `true` if a command was removed
+    pub fn remove_tombstoned(&mut self, tombstone: &HoardCmd) -> bool {
+        let count = self.commands.len();
+        let tombstoned =
+            |x: &HoardCmd| x.namespace == tombstone.namespace && x.name == tombstone.name;
+        self.emit_removed(tombstoned);
+        self.commands.retain(|x| !tombstoned(x));
+        if count == self.commands.len() {
+            return false;
+        }
+        self.prune_empty_namespaces();
+        true
+    }
+
+    #[allow(dead_code)]
+    /// Loads all trove files (`.yml`/`.yaml`) in directory `path` and merges them into a single trove
+    ///
+    /// Files are merged in alphabetical order. If `progress` is set, it is called with `(done, total)` over all commands to merge
+    pub fn load_dir(
+        path: &Path,
+        mut progress: Option<&mut dyn FnMut(usize, usize)>,
+    ) -> Result<Self> {
+        let mut paths: Vec<PathBuf> = fs::read_dir(path)?
+            .filter_map(|entry| entry.ok().map(|e| e.path()))
+            .filter(|p| {
+                p.is_file()
+                    && p.extension()
+                        .is_some_and(|ext| ext == "yml" || ext == "yaml")
+            })
+            .collect();
+        paths.sort();
+
+        let troves: Vec<Self> = paths
+            .into_iter()
+            .map(|p| Self::load_trove_file(&Some(p)))
+            .collect();
+        let total: usize = troves.iter().map(|t| t.commands.len()).sum();
+
+        let mut trove = Self::default();
+        let mut offset = 0;
+        for other in &troves {
+            match progress.as_mut() {
+                Some(report) => {
+                    let mut report_offset = |done: usize, _: usize| report(offset + done, total);
+                    trove.merge_trove_with_progress(other, Some(&mut report_offset));
+                }
+                None => {
+                    trove.merge_trove(other);
+                }
+            }
+            offset += other.commands.len();
+        }
+        Ok(trove)
+    }
+
+    #[allow(dead_code)]
+    /// Loads the trove files at `paths` and merges them into a single trove, in order
+    ///
+    /// Later files win on conflicts, like `merge_trove`. Every command keeps the path of the file it was loaded from in `source`.
+    /// Missing files are skipped, files that can not be parsed fail the whole load
+    pub fn load_and_merge(paths: &[PathBuf]) -> Result<Self, TroveError> {
+        let mut trove = Self::default();
+        for path in paths {
+            let mut other = Self::try_load_trove_file(&Some(path.clone()))?;
+            for command in &mut other.commands {
+                command.source = Some(path.clone());
+            }
+            trove.merge_trove(&other);
+        }
+        Ok(trove)
+    }
+
+    #[allow(dead_code)]
+    /// Loads only the commands of `namespace` from the trove file at `path`
+    ///
+    /// The file is parsed into a generic yaml value first, so only commands of `namespace` are deserialized.
+    /// `namespace` may be a namespace alias defined in the file
+    pub fn load_namespace_only(path: &Path, namespace: &str) -> Result<Self, TroveError> {
+        let value: serde_yaml::Value = serde_yaml::from_reader(fs::File::open(path)?)?;
+        let namespace = value
+            .get("namespace_aliases")
+            .and_then(|aliases| aliases.get(namespace))
+            .and_then(serde_yaml::Value::as_str)
+            .unwrap_or(namespace);
+
+        let mut commands = Vec::new();
+        if let Some(entries) = value
+            .get("commands")
+            .and_then(serde_yaml::Value::as_sequence)
+        {
+            for entry in entries {
+                if entry.get("namespace").and_then(serde_yaml::Value::as_str) == Some(namespace) {
+                    let mut command: HoardCmd = serde_yaml::from_value(entry.clone())?;
+                    command.mut_dedupe_tags();
+                    commands.push(command);
+                }
+            }
+        }
+        let mut trove = Self::from_commands(&commands);
+        if let Some(version) = value.get("version").and_then(serde_yaml::Value::as_str) {
+            trove.version = version.to_string();
+        }
+        Ok(trove)
+    }
+
+    pub fn print_trove(&self, config: &HoardConfig) {
+        Self::print_commands(&self.sorted_commands(), config);
+    }
+
+    /// Get page `page` (starting at `1`) of the `sorted_commands` with `per_page` commands per page
+    ///
+    /// Returns the commands of the page together with the total number of pages, which is at least `1`
+    fn paged_commands(&self, page: usize, per_page: usize) -> (Vec<&HoardCmd>, usize) {
+        let per_page = per_page.max(1);
+        let commands = self.sorted_commands();
+        let pages = commands.len().div_ceil(per_page).max(1);
+        let page: Vec<&HoardCmd> = commands
+            .into_iter()
+            .skip(page.saturating_sub(1).saturating_mul(per_page))
+            .take(per_page)
+            .collect();
+        (page, pages)
+    }
+
+    #[allow(dead_code)]
+    /// Print page `page` (starting at `1`) of the trove with `per_page` commands per page, followed by a page footer
+    ///
+    /// Commands are sorted by namespace, then by name, so page boundaries are the same between invocations
+    pub fn print_trove_paged(&self, config: &HoardConfig, page: usize, per_page: usize) {
+        let (commands, pages) = self.paged_commands(page, per_page);
+        if page == 0 || page > pages {
+            println!("Page {page} does not exist, the trove has {pages} page(s)");
+            return;
+        }
+        Self::print_commands(&commands, config);
+        println!("Page {page}/{pages} ({} commands)", self.commands.len());
+    }
+
+    #[allow(dead_code)]
+    /// Print the commands of `namespace` in the same table as `print_trove`
+    pub fn print_namespace(&self, config: &HoardConfig, namespace: &str) {
+        let commands = self.commands_in_namespace(namespace);
+        if commands.is_empty() {
+            println!("No commands in namespace '{namespace}'");
+        } else {
+            Self::print_commands(&commands, config);
+        }
     }
 
-    pub fn print_trove(&self) {
+    fn print_commands(commands: &[&HoardCmd], config: &HoardConfig) {
+        let tag_colors = configured_tag_colors(config);
         // Create the table
         let mut table = Table::new();
         // Add header
         table.add_row(row!["Name", "namespace", "command", "description", "tags"]);
-        // Iterate through trove and populate table
-        self.commands.iter().for_each(|c| {
+        // Iterate through the commands and populate table
+        for c in commands {
             table.add_row(Row::new(vec![
-                // Name
-                Cell::new(&c.name[..])
-                    .with_style(Attr::Bold)
-                    .with_style(Attr::ForegroundColor(color::GREEN)),
+                // Name, commands requiring confirmation are flagged
+                if c.confirm {
+                    Cell::new(&format!("{} (!)", c.name))
+                        .with_style(Attr::Bold)
+                        .with_style(Attr::ForegroundColor(color::RED))
+                } else {
+                    Cell::new(&c.name[..])
+                        .with_style(Attr::Bold)
+                        .with_style(Attr::ForegroundColor(color::GREEN))
+                },
                 // namespace
                 Cell::new(&c.namespace[..]),
                 // command
@@ -303,9 +1894,9 @@ impl Trove {
                 // description
                 Cell::new(&c.description[..]),
                 // tags
-                Cell::new(&c.get_tags_as_string()),
+                Cell::new(&colored_tags(&c.tags(), &tag_colors)),
             ]));
-        });
+        }
         // Print the table to stdout
         table.printstd();
     }
@@ -437,7 +2028,7 @@ mod test_commands {
     fn test_add_and_remove_command() {
         // create a new trove, add a command, verify the command is not empty,
         // then remove the command and verify the trove to be empty.
-        // the namespace that got added should still exist
+        // the namespace that got added should be pruned with its last command
         let mut trove = Trove::default();
         let mut command = HoardCmd::default();
         command.name = "test".to_string();
@@ -452,8 +2043,8 @@ mod test_commands {
         let val = trove.remove_command("test");
         assert!(val.is_ok());
         assert!(trove.is_empty());
-        // namespace has to be present now
-        assert!(trove.namespaces.contains("test"));
+        // namespace has to be gone now
+        assert!(!trove.namespaces.contains("test"));
     }
 
     #[test]
@@ -524,6 +2115,145 @@ mod test_commands {
         assert!(trove.is_empty());
     }
 
+    #[test]
+    fn test_lookup_and_update_by_id() {
+        let mut trove = Trove::default();
+        let command = HoardCmd::default()
+            .with_name("test")
+            .with_namespace("test")
+            .with_command("echo test");
+        let id = command.id.clone();
+        assert!(trove.add_command(command, true).is_ok());
+
+        let renamed = trove
+            .get_command_by_id(&id)
+            .unwrap()
+            .clone()
+            .with_name("renamed");
+        assert!(trove.update_command_by_id(&renamed).is_ok());
+        assert_eq!("renamed", trove.get_command_by_id(&id).unwrap().name);
+
+        assert!(trove.remove_command_by_id(&id).is_ok());
+        assert!(trove.is_empty());
+        assert!(!trove.has_namespace("test"));
+        assert!(trove.remove_command_by_id(&id).is_err());
+    }
+
+    #[test]
+    fn suffixed_commands_get_new_ids() {
+        let command = HoardCmd::default()
+            .with_name("test")
+            .with_namespace("test")
+            .with_command("echo test");
+        let mut trove = Trove::from_commands(std::slice::from_ref(&command));
+        let colliding = command.clone().with_command("echo other");
+        trove.add_command(colliding.clone(), false).unwrap();
+        trove.add_commands(vec![colliding], false).unwrap();
+
+        let ids: HashSet<&str> = trove.commands.iter().map(|c| c.id.as_str()).collect();
+        assert_eq!(3, ids.len());
+        trove.remove_command_by_id(&command.id).unwrap();
+        assert_eq!(2, trove.commands.len());
+    }
+
+    #[test]
+    fn test_missing_ids_assigned_on_load() {
+        let trove_string = "version: 2.0.0\ncommands:\n- name: test\n  namespace: test\n  command: echo test\n  description: ''\n  tags: []\n";
+        let trove = Trove::load_trove_from_string(trove_string);
+        assert_eq!(1, trove.commands.len());
+        assert!(!trove.commands[0].id.is_empty());
+        // The id is persisted once the trove is written again
+        assert!(trove.to_yaml().contains(&trove.commands[0].id));
+    }
+
+    #[test]
+    fn test_merge_trove_reports_progress() {
+        let commands: Vec<HoardCmd> = (0..250)
+            .map(|i| {
+                HoardCmd::default()
+                    .with_name(&format!("name{i}"))
+                    .with_namespace("test")
+                    .with_command("echo test")
+            })
+            .collect();
+        let other = Trove::from_commands(&commands);
+        let mut trove = Trove::default();
+        let mut reported = Vec::new();
+        let mut progress = |done: usize, total: usize| reported.push((done, total));
+        assert!(trove.merge_trove_with_progress(&other, Some(&mut progress)));
+        assert_eq!(vec![(100, 250), (200, 250), (250, 250)], reported);
+        assert_eq!(250, trove.commands.len());
+    }
+
+    #[test]
+    fn test_load_dir_merges_all_troves() {
+        let tmp_dir = tempfile::tempdir().unwrap();
+        let command1 = HoardCmd::default()
+            .with_name("name1")
+            .with_namespace("test")
+            .with_command("echo 1");
+        let command2 = HoardCmd::default()
+            .with_name("name2")
+            .with_namespace("test")
+            .with_command("echo 2");
+        Trove::from_commands(&[command1])
+            .save_trove_file(&tmp_dir.path().join("a.yml"))
+            .unwrap();
+        Trove::from_commands(&[command2])
+            .save_trove_file(&tmp_dir.path().join("b.yaml"))
+            .unwrap();
+        fs::write(tmp_dir.path().join("notes.txt"), "not a trove").unwrap();
+
+        let mut reported = Vec::new();
+        let mut progress = |done: usize, total: usize| reported.push((done, total));
+        let trove = Trove::load_dir(tmp_dir.path(), Some(&mut progress)).unwrap();
+        assert_eq!(2, trove.commands.len());
+        assert_eq!(vec![(1, 2), (2, 2)], reported);
+    }
+
+    #[test]
+    fn test_transaction_rolls_back_on_error() {
+        let command = HoardCmd::default()
+            .with_name("test")
+            .with_namespace("test")
+            .with_command("echo test");
+        let mut trove = Trove::from_commands(&[command]);
+        let result: Result<(), TroveError> = trove.transaction(|t| {
+            t.remove_command("test").unwrap();
+            assert!(t.is_empty());
+            Err(TroveError::Archive("failure".to_string()))
+        });
+        assert!(result.is_err());
+        assert_eq!(1, trove.commands.len());
+
+        let result = trove.transaction(|t| {
+            t.remove_command("test").unwrap();
+            Ok(42)
+        });
+        assert_eq!(42, result.unwrap());
+        assert!(trove.is_empty());
+    }
+
+    #[test]
+    fn test_namespace_alias() {
+        let command = HoardCmd::default()
+            .with_name("pods")
+            .with_namespace("kubernetes")
+            .with_command("kubectl get pods");
+        let mut trove = Trove::from_commands(&[command]);
+        assert!(trove.set_namespace_alias("kubernetes", "k8s").is_err());
+        assert!(trove.set_namespace_alias("k8s", "kubernetes").is_ok());
+        assert_eq!("kubernetes", trove.resolve_namespace_alias("k8s"));
+        assert_eq!("other", trove.resolve_namespace_alias("other"));
+
+        // Aliases are persisted with the trove
+        let reloaded = Trove::load_trove_from_string(&trove.to_yaml());
+        assert_eq!("kubernetes", reloaded.resolve_namespace_alias("k8s"));
+
+        assert!(trove.remove_namespace_commands("k8s").is_ok());
+        assert!(trove.is_empty());
+    }
+
     #[test]
     fn test_is_empty_new_trove() {
         // create a new trove and check if it is empty
@@ -538,4 +2268,1271 @@ mod test_commands {
         // Should not contain a command
         assert!(!trove.namespaces.contains("test"));
     }
+
+    #[test]
+    fn size_budget() {
+        let small = HoardCmd::default()
+            .with_name("small")
+            .with_namespace("small")
+            .with_command("ls");
+        let large = HoardCmd::default()
+            .with_name("large")
+            .with_namespace("large")
+            .with_command(&"echo ".repeat(100));
+        let trove = Trove::from_commands(&[small.clone(), large]);
+        let size = trove.serialized_size();
+        assert_eq!(trove.to_yaml().len(), size);
+        assert!(trove.fits_within(size));
+        assert!(!trove.fits_within(size - 1));
+        assert!(trove.suggest_namespace_splits(size).is_empty());
+
+        let budget = Trove::from_commands(&[small]).serialized_size();
+        assert_eq!(vec!["large"], trove.suggest_namespace_splits(budget));
+    }
+
+    #[test]
+    fn namespaces_with_counts() {
+        let commands = vec![
+            HoardCmd::default().with_name("a").with_namespace("git"),
+            HoardCmd::default().with_name("b").with_namespace("aws"),
+            HoardCmd::default().with_name("c").with_namespace("git"),
+            HoardCmd::default().with_name("d").with_namespace("k8s"),
+        ];
+        let trove = Trove::from_commands(&commands);
+        assert_eq!(
+            vec![
+                ("aws".to_string(), 1),
+                ("git".to_string(), 2),
+                ("k8s".to_string(), 1)
+            ],
+            trove.namespaces_with_counts(false)
+        );
+        assert_eq!(
+            vec![
+                ("git".to_string(), 2),
+                ("aws".to_string(), 1),
+                ("k8s".to_string(), 1)
+            ],
+            trove.namespaces_with_counts(true)
+        );
+    }
+
+    #[test]
+    fn duplicate_tags_are_removed_on_load_and_add() {
+        let trove_string = "---\nversion: 1.0.0\ncommands:\n  - name: test\n    namespace: test\n    command: echo test\n    description: test\n    tags:\n      - a\n      - b\n      - a\n";
+        let trove = Trove::load_trove_from_string(trove_string);
+        assert_eq!(vec!["a", "b"], trove.commands[0].tags);
+
+        let mut trove = Trove::default();
+        let command = HoardCmd::default()
+            .with_name("test")
+            .with_namespace("test")
+            .with_command("echo test")
+            .with_tags(vec!["x".to_string(), "x".to_string()]);
+        trove.add_command(command, true).unwrap();
+        assert_eq!(vec!["x"], trove.commands[0].tags);
+    }
+
+    #[test]
+    fn resolve_qualified_command() {
+        let commands = vec![
+            HoardCmd::default()
+                .with_name("deploy")
+                .with_namespace("prod")
+                .with_command("deploy #env=prod! #version!"),
+            HoardCmd::default()
+                .with_name("deploy")
+                .with_namespace("stage")
+                .with_command("deploy #env=stage! #version!"),
+        ];
+        let trove = Trove::from_commands(&commands);
+        let config = HoardConfig::default();
+        let mut values = HashMap::new();
+        values.insert("version".to_string(), "1.2".to_string());
+
+        assert_eq!(
+            "deploy stage 1.2",
+            trove.resolve("stage/deploy", &values, &config).unwrap()
+        );
+        assert_eq!(
+            "deploy prod 1.2",
+            trove.resolve("deploy", &values, &config).unwrap()
+        );
+        assert!(matches!(
+            trove.resolve("dev/deploy", &values, &config),
+            Err(TroveError::CommandNotFound(_))
+        ));
+        assert!(matches!(
+            trove.resolve("prod/deploy", &HashMap::new(), &config),
+            Err(TroveError::MissingParameters { .. })
+        ));
+    }
+
+    #[test]
+    fn load_namespace_only() {
+        let commands = vec![
+            HoardCmd::default()
+                .with_name("pods")
+                .with_namespace("k8s")
+                .with_command("kubectl get pods"),
+            HoardCmd::default()
+                .with_name("status")
+                .with_namespace("git")
+                .with_command("git status"),
+        ];
+        let mut trove = Trove::from_commands(&commands);
+        trove.set_namespace_alias("kube", "k8s").unwrap();
+        let dir = tempfile::tempdir().unwrap();
+        let path = dir.path().join("trove.yml");
+        trove.save_trove_file(&path).unwrap();
+
+        let loaded = Trove::load_namespace_only(&path, "kube").unwrap();
+        assert_eq!(1, loaded.commands.len());
+        assert_eq!("pods", loaded.commands[0].name);
+        assert_eq!(vec!["k8s"], loaded.namespaces());
+        assert!(Trove::load_namespace_only(&path, "aws").unwrap().is_empty());
+        assert!(Trove::load_namespace_only(&dir.path().join("missing.yml"), "k8s").is_err());
+    }
+
+    #[test]
+    fn normalize_namespace_separators() {
+        let commands = vec![
+            HoardCmd::default()
+                .with_name("a")
+                .with_namespace("work.k8s"),
+            HoardCmd::default()
+                .with_name("b")
+                .with_namespace("work/aws"),
+            HoardCmd::default()
+                .with_name("c")
+                .with_namespace("work-git"),
+        ];
+        let mut trove = Trove::from_commands(&commands);
+        trove.set_namespace_alias("kube", "work.k8s").unwrap();
+
+        assert_eq!(1, trove.normalize_separators('/'));
+        assert_eq!(vec!["work-git", "work/aws", "work/k8s"], trove.namespaces());
+        assert!(trove.namespaces.contains("work/k8s"));
+        assert!(!trove.namespaces.contains("work.k8s"));
+        assert_eq!("work/k8s", trove.resolve_namespace_alias("kube"));
+        assert_eq!(0, trove.normalize_separators('/'));
+    }
+
+    #[test]
+    fn rebuild_namespaces() {
+        let mut trove =
+            Trove::from_commands(&[HoardCmd::default().with_name("a").with_namespace("git")]);
+        trove.namespaces.insert("orphan".to_string());
+        trove
+            .commands
+            .push(HoardCmd::default().with_name("b").with_namespace("k8s"));
+
+        trove.rebuild_namespaces();
+        let mut namespaces: Vec<&String> = trove.namespaces.iter().collect();
+        namespaces.sort();
+        assert_eq!(vec!["git", "k8s"], namespaces);
+    }
+
+    #[test]
+    fn multi_line_commands_round_trip() {
+        let script = "for f in *.log; do  \n  gzip \"$f\"\ndone \n";
+        let command = HoardCmd::default()
+            .with_name("compress")
+            .with_namespace("scripts")
+            .with_command(script);
+        let trove = Trove::from_commands(&[command]);
+        let yaml = trove.to_yaml();
+        assert!(yaml.contains("command: |\n"));
+
+        let loaded = Trove::load_trove_from_string(&yaml);
+        assert_eq!(script, loaded.commands[0].command);
+    }
+
+    #[test]
+    fn whitespace_led_multi_line_values_survive_save_and_load() {
+        let tmp_dir = tempfile::tempdir().unwrap();
+        let path = tmp_dir.path().join("trove.yml");
+        let command = |name: &str, value: &str| {
+            HoardCmd::default()
+                .with_name(name)
+                .with_namespace("scripts")
+                .with_command(value)
+                .with_description(value)
+        };
+        let trove = Trove::from_commands(&[
+            command("tab", "\techo a\necho b"),
+            command("space", "  echo a\necho b\n"),
+        ]);
+        trove.save_trove_file(&path).unwrap();
+
+        let loaded = Trove::try_load_trove_file(&Some(path)).unwrap();
+        assert_eq!(trove.commands.len(), loaded.commands.len());
+        for saved in &trove.commands {
+            let loaded = loaded.get_command(&saved.name).unwrap();
+            assert_eq!(saved.command, loaded.command);
+            assert_eq!(saved.description, loaded.description);
+        }
+    }
+
+    #[test]
+    fn unused_namespaces() {
+        let mut trove =
+            Trove::from_commands(&[HoardCmd::default().with_name("a").with_namespace("git")]);
+        assert!(trove.unused_namespaces().is_empty());
+        trove.namespaces.insert("old".to_string());
+        trove.namespaces.insert("archived".to_string());
+        assert_eq!(vec!["archived", "old"], trove.unused_namespaces());
+    }
+
+    #[test]
+    fn split_namespace() {
+        let commands = vec![
+            HoardCmd::default()
+                .with_name("pods")
+                .with_namespace("work")
+                .with_command("kubectl get pods"),
+            HoardCmd::default()
+                .with_name("status")
+                .with_namespace("work")
+                .with_command("git status"),
+            HoardCmd::default()
+                .with_name("pods")
+                .with_namespace("k8s")
+                .with_command("kubectl get pods -A"),
+        ];
+        let mut trove = Trove::from_commands(&commands);
+        let moved = trove.split_namespace("work", "k8s", |c| c.command.starts_with("kubectl"));
+        assert_eq!(1, moved);
+        assert_eq!("k8s", trove.commands[0].namespace);
+        assert!(trove.commands[0].name.starts_with("pods-"));
+        assert_eq!("work", trove.commands[1].namespace);
+        assert_eq!(0, trove.split_namespace("work", "work", |_| true));
+
+        assert_eq!(1, trove.split_namespace("work", "git", |_| true));
+        assert!(trove.namespaces.contains("git"));
+    }
+
+    #[test]
+    fn from_lines() {
+        let lines = "\
+# show the working tree status
+git status
+
+git log --oneline
+# list pods
+#   in all namespaces
+kubectl get pods -A
+git status
+/usr/bin/git fetch
+";
+        let trove = Trove::from_lines(lines, "scratch");
+        let commands: Vec<(&str, &str, &str)> = trove
+            .commands
+            .iter()
+            .map(|c| (c.name.as_str(), c.command.as_str(), c.description.as_str()))
+            .collect();
+        assert_eq!(
+            vec![
+                ("git", "git status", "show the working tree status"),
+                ("git-2", "git log --oneline", ""),
+                (
+                    "kubectl",
+                    "kubectl get pods -A",
+                    "list pods in all namespaces"
+                ),
+                ("git-3", "/usr/bin/git fetch", ""),
+            ],
+            commands
+        );
+        assert_eq!(vec!["scratch"], trove.namespaces());
+        assert!(trove.commands.iter().all(HoardCmd::is_valid));
+    }
+
+    #[test]
+    fn write_yaml_matches_to_yaml() {
+        let command = HoardCmd::default()
+            .with_name("multi")
+            .with_namespace("test")
+            .with_command("echo a\necho b")
+            .with_description("two lines");
+        let trove = Trove::from_commands(&[command]);
+        let mut written = Vec::new();
+        trove.write_yaml(&mut written).unwrap();
+        assert_eq!(trove.to_yaml(), String::from_utf8(written).unwrap());
+    }
+
+    #[test]
+    fn has_namespace() {
+        let command = HoardCmd::default()
+            .with_name("status")
+            .with_namespace("git")
+            .with_command("git status");
+        let mut trove = Trove::from_commands(&[command]);
+        trove.add_namespace("stale");
+        trove.set_namespace_alias("vcs", "git").unwrap();
+        assert!(trove.has_namespace("git"));
+        assert!(!trove.has_namespace("stale"));
+        assert!(!trove.has_namespace("vcs"));
+        assert!(!trove.has_namespace("Git"));
+        assert!(trove
+            .namespaces()
+            .iter()
+            .all(|namespace| trove.has_namespace(namespace)));
+
+        trove.options.case_sensitive_namespaces = false;
+        assert!(trove.has_namespace("Git"));
+        assert!(trove.has_namespace("GIT"));
+        assert!(!trove.has_namespace("stale"));
+    }
+
+    #[test]
+    fn load_trove_lenient_skips_bad_commands() {
+        let tmp_dir = tempfile::tempdir().unwrap();
+        let path = tmp_dir.path().join("trove.yml");
+        let good = HoardCmd::default()
+            .with_name("good")
+            .with_namespace("test")
+            .with_command("echo good");
+        let mut value = serde_yaml::to_value(Trove::from_commands(&[good])).unwrap();
+        let bad: serde_yaml::Value =
+            serde_yaml::from_str("{name: bad, namespace: test, usage_count: many}").unwrap();
+        value["commands"].as_sequence_mut().unwrap().push(bad);
+        let yaml = serde_yaml::to_string(&value).unwrap();
+        fs::write(&path, yaml).unwrap();
+
+        let (trove, errors) = Trove::load_trove_lenient(&path);
+        assert_eq!(1, trove.commands.len());
+        assert_eq!("good", trove.commands[0].name);
+        assert_eq!(1, errors.len());
+        assert!(matches!(
+            &errors[0],
+            TroveError::InvalidCommand { index: 1, name: Some(name), .. } if name == "bad"
+        ));
+
+        let (trove, errors) = Trove::load_trove_lenient(&tmp_dir.path().join("missing.yml"));
+        assert!(trove.is_empty());
+        assert!(matches!(errors[..], [TroveError::Io(_)]));
+    }
+
+    #[test]
+    fn load_trove_file_validated_quarantines_invalid_commands() {
+        let tmp_dir = tempfile::tempdir().unwrap();
+        let path = tmp_dir.path().join("trove.yml");
+        let good = HoardCmd::default()
+            .with_name("good")
+            .with_namespace("test")
+            .with_command("echo good");
+        let unfinished = HoardCmd::default()
+            .with_name("unfinished")
+            .with_namespace("draft")
+            .with_command("echo unfinished");
+        let mut yaml = Trove::from_commands(&[good, unfinished]).to_yaml();
+        yaml = yaml.replace("command: echo unfinished", "command: ''");
+        fs::write(&path, yaml).unwrap();
+
+        let (trove, invalid) = Trove::load_trove_file_validated(&Some(path)).unwrap();
+        assert_eq!(1, trove.commands.len());
+        assert_eq!("good", trove.commands[0].name);
+        assert!(!trove.has_namespace("draft"));
+        assert_eq!(1, invalid.len());
+        assert_eq!("unfinished", invalid[0].name);
+
+        let (trove, invalid) =
+            Trove::load_trove_file_validated(&Some(tmp_dir.path().join("missing.yml"))).unwrap();
+        assert!(trove.is_empty());
+        assert!(invalid.is_empty());
+    }
+
+    #[test]
+    fn load_and_merge_keeps_sources() {
+        let tmp_dir = tempfile::tempdir().unwrap();
+        let work = tmp_dir.path().join("work.yml");
+        let personal = tmp_dir.path().join("personal.yml");
+        let command = |name: &str, command: &str| {
+            HoardCmd::default()
+                .with_name(name)
+                .with_namespace("shell")
+                .with_command(command)
+        };
+        Trove::from_commands(&[command("deploy", "make deploy"), command("ls", "ls")])
+            .save_trove_file(&work)
+            .unwrap();
+        Trove::from_commands(&[command("ls", "ls -la"), command("top", "htop")])
+            .save_trove_file(&personal)
+            .unwrap();
+
+        let trove = Trove::load_and_merge(&[work.clone(), personal.clone()]).unwrap();
+        assert_eq!(3, trove.commands.len());
+        let source = |name: &str| {
+            trove
+                .get_command(&format!("shell/{name}"))
+                .unwrap()
+                .source
+                .clone()
+        };
+        assert_eq!(Some(work), source("deploy"));
+        assert_eq!(Some(personal.clone()), source("ls"));
+        assert_eq!("ls -la", trove.get_command("shell/ls").unwrap().command);
+
+        // The source is never saved
+        assert!(!trove.to_yaml().contains("source"));
+        fs::write(&personal, "not: [a trove").unwrap();
+        assert!(Trove::load_and_merge(&[personal]).is_err());
+    }
+
+    #[test]
+    fn record_usage_ranks_most_used() {
+        let command = |name: &str| {
+            HoardCmd::default()
+                .with_name(name)
+                .with_namespace("shell")
+                .with_command(name)
+        };
+        let mut trove = Trove::from_commands(&[command("ls"), command("top"), command("du")]);
+        trove.record_usage("top").unwrap();
+        trove.record_usage("shell/top").unwrap();
+        trove.record_usage("du").unwrap();
+        assert!(trove.record_usage("missing").is_err());
+
+        let names: Vec<&str> = trove.most_used(2).iter().map(|c| c.name.as_str()).collect();
+        assert_eq!(vec!["top", "du"], names);
+        assert_eq!(2, trove.get_command("top").unwrap().usage_count);
+        assert_eq!(3, trove.most_used(10).len());
+
+        // Troves stored before usage was counted still load
+        let yaml = trove.to_yaml().replace("    usage_count: 2\n", "");
+        let loaded = Trove::load_trove_from_string(&yaml);
+        assert_eq!(3, loaded.commands.len());
+        assert_eq!(0, loaded.get_command("top").unwrap().usage_count);
+    }
+
+    #[test]
+    fn snapshot_and_restore() {
+        let command = HoardCmd::default()
+            .with_name("status")
+            .with_namespace("git")
+            .with_command("git status");
+        let mut trove = Trove::from_commands(&[command]);
+        let snapshot = trove.snapshot();
+        trove.remove_namespace_commands("git").unwrap();
+        trove.set_namespace_alias("vcs", "git").unwrap();
+        assert!(trove.is_empty());
+        trove.restore(snapshot);
+        assert_eq!(1, trove.commands.len());
+        assert!(trove.namespace_aliases.is_empty());
+    }
+
+    #[test]
+    fn commands_created_since() {
+        let day = crate::core::search::DAY;
+        let mut old = HoardCmd::default()
+            .with_name("old")
+            .with_namespace("shell")
+            .with_command("ls");
+        old.created -= 30 * day;
+        let new = HoardCmd::default()
+            .with_name("new")
+            .with_namespace("shell")
+            .with_command("du");
+        let trove = Trove::from_commands(&[old.clone(), new]);
+        let since = Utc::now() - chrono::Duration::days(7);
+        let names: Vec<&str> = trove
+            .commands_created_since(since)
+            .iter()
+            .map(|c| c.name.as_str())
+            .collect();
+        assert_eq!(vec!["new"], names);
+
+        // Saving and loading keeps the creation time
+        let loaded = Trove::load_trove_from_string(&trove.to_yaml());
+        assert_eq!(old.created, loaded.get_command("old").unwrap().created);
+    }
+
+    #[test]
+    fn resolve_namespace_with_config_aliases() {
+        let mut trove = Trove::from_commands(&[HoardCmd::default()
+            .with_name("pods")
+            .with_namespace("kubernetes")
+            .with_command("kubectl get pods")]);
+        trove.set_namespace_alias("kube", "kubernetes").unwrap();
+        let config = HoardConfig {
+            namespace_aliases: HashMap::from([
+                ("k".to_string(), "kubernetes".to_string()),
+                ("kb".to_string(), "kube".to_string()),
+                ("d".to_string(), "docker".to_string()),
+            ]),
+            ..HoardConfig::default()
+        };
+
+        assert_eq!("kubernetes", trove.resolve_namespace("k", &config));
+        assert_eq!("kubernetes", trove.resolve_namespace("kb", &config));
+        assert_eq!("kubernetes", trove.resolve_namespace("kube", &config));
+        assert_eq!("d", trove.resolve_namespace("d", &config));
+        assert_eq!("git", trove.resolve_namespace("git", &config));
+        let namespace = trove.resolve_namespace("k", &config);
+        assert_eq!(1, trove.commands_in_namespace(&namespace).len());
+    }
+
+    #[test]
+    fn try_update_command_by_name() {
+        let command = |name: &str, namespace: &str, command: &str| {
+            HoardCmd::default()
+                .with_name(name)
+                .with_namespace(namespace)
+                .with_command(command)
+        };
+        let mut trove = Trove::from_commands(&[
+            command("status", "git", "git status"),
+            command("log", "git", "git log"),
+            command("log", "svn", "svn log"),
+        ]);
+        trove
+            .try_update_command_by_name(&command("status", "git", "git status -sb"))
+            .unwrap();
+        assert_eq!("git status -sb", trove.commands[0].command);
+        trove
+            .try_update_command_by_name(&command("log", "svn", "svn log -l 5"))
+            .unwrap();
+        assert_eq!(
+            "svn log -l 5",
+            trove.get_command("svn/log").unwrap().command
+        );
+        trove
+            .try_update_command_by_name(&command("status", "vcs", "git status"))
+            .unwrap();
+        assert!(trove.has_namespace("vcs"));
+
+        assert!(matches!(
+            trove.try_update_command_by_name(&command("stauts", "vcs", "git status")),
+            Err(TroveError::CommandNotFound(_))
+        ));
+        assert!(matches!(
+            trove.try_update_command_by_name(&command("log", "vcs", "log")),
+            Err(TroveError::AmbiguousCommand { .. })
+        ));
+        trove
+            .remove_command_by_id(&trove.commands[2].id.clone())
+            .unwrap();
+        assert!(matches!(
+            trove.try_update_command_by_name(&command("log", "vcs", "log")),
+            Ok(())
+        ));
+    }
+
+    #[test]
+    fn untagged_commands() {
+        let command = |name: &str, tags: Vec<&str>| {
+            let mut command = HoardCmd::default()
+                .with_name(name)
+                .with_namespace("test")
+                .with_command("echo");
+            command.tags = tags.into_iter().map(str::to_string).collect();
+            command
+        };
+        let trove = Trove::from_commands(&[
+            command("none", vec![]),
+            command("blank", vec!["", "  "]),
+            command("tagged", vec![" prod "]),
+        ]);
+        let names = |commands: Vec<&HoardCmd>| -> Vec<String> {
+            commands.iter().map(|c| c.name.clone()).collect()
+        };
+        assert_eq!(vec!["none", "blank"], names(trove.untagged_commands()));
+        assert_eq!(vec!["tagged"], names(trove.commands_with_tag("prod")));
+    }
+
+    #[test]
+    fn json_round_trip() {
+        let commands: Vec<HoardCmd> = ["b", "a", "c"]
+            .iter()
+            .map(|namespace| {
+                HoardCmd::default()
+                    .with_name("test")
+                    .with_namespace(namespace)
+                    .with_command("echo test")
+            })
+            .collect();
+        let trove = Trove::from_commands(&commands);
+        // Commands are saved in listing order
+        let sorted: Vec<HoardCmd> = trove.sorted_commands().into_iter().cloned().collect();
+        let json = trove.to_json();
+        let value: serde_json::Value = serde_json::from_str(&json).unwrap();
+        assert_eq!(serde_json::json!(["a", "b", "c"]), value["namespaces"]);
+        let loaded = Trove::load_trove_from_json_string(&json);
+        assert_eq!(sorted, loaded.commands);
+
+        let tmp_dir = tempfile::tempdir().unwrap();
+        let path = tmp_dir.path().join("trove.json");
+        trove.save_trove_file_as(&path).unwrap();
+        assert!(fs::read_to_string(&path).unwrap().starts_with('{'));
+        assert_eq!(sorted, Trove::load_trove_file(&Some(path)).commands);
+        let path = tmp_dir.path().join("trove.yml");
+        trove.save_trove_file_as(&path).unwrap();
+        assert_eq!(trove.to_yaml(), fs::read_to_string(&path).unwrap());
+    }
+
+    #[test]
+    fn load_configured_merges_additional_troves() {
+        let tmp_dir = tempfile::tempdir().unwrap();
+        let command = |name: &str| {
+            HoardCmd::default()
+                .with_name(name)
+                .with_namespace("test")
+                .with_command(&format!("echo {name}"))
+        };
+        let primary = tmp_dir.path().join("trove.yml");
+        let overlay = tmp_dir.path().join("team.yml");
+        let broken = tmp_dir.path().join("broken.yml");
+        Trove::from_commands(&[command("mine")])
+            .save_trove_file(&primary)
+            .unwrap();
+        Trove::from_commands(&[command("team")])
+            .save_trove_file(&overlay)
+            .unwrap();
+        fs::write(&broken, "commands: [").unwrap();
+
+        let config = HoardConfig {
+            trove_path: Some(primary),
+            additional_troves: vec![overlay, broken, tmp_dir.path().join("missing.yml")],
+            ..HoardConfig::default()
+        };
+        let trove = Trove::load_configured(&config);
+        let mut names: Vec<&str> = trove.commands.iter().map(|c| c.name.as_str()).collect();
+        names.sort_unstable();
+        assert_eq!(vec!["mine", "team"], names);
+    }
+
+    #[test]
+    fn try_load_trove_file() {
+        let tmp_dir = tempfile::tempdir().unwrap();
+        let path = tmp_dir.path().join("trove.yml");
+        assert!(Trove::try_load_trove_file(&None).unwrap().is_empty());
+        assert!(Trove::try_load_trove_file(&Some(path.clone()))
+            .unwrap()
+            .is_empty());
+
+        fs::write(&path, "commands: [").unwrap();
+        assert!(matches!(
+            Trove::try_load_trove_file(&Some(path.clone())),
+            Err(TroveError::Yaml(_))
+        ));
+        assert!(Trove::load_trove_file(&Some(path)).is_empty());
+    }
+
+    #[test]
+    fn case_insensitive_namespaces() {
+        let command = |namespace: &str| {
+            HoardCmd::default()
+                .with_name("status")
+                .with_namespace(namespace)
+                .with_command("git status")
+        };
+        let mut trove = Trove::default();
+        trove.add_command(command("Git"), false).unwrap();
+        trove.add_command(command("git"), false).unwrap();
+        assert_eq!(vec!["Git", "git"], trove.namespaces());
+
+        let config = HoardConfig {
+            case_sensitive_namespaces: false,
+            ..HoardConfig::default()
+        };
+        let mut trove = Trove::default();
+        trove.apply_config(&config);
+        trove.add_command(command("Git"), false).unwrap();
+        assert!(trove.get_command_collision(&command("GIT")).is_some());
+        // The same command in the same namespace is not added again
+        assert!(!trove.add_command(command("git"), false).unwrap());
+        assert_eq!(vec!["Git"], trove.namespaces());
+        trove.add_namespace("gIT");
+        assert_eq!(1, trove.namespaces.len());
+    }
+
+    #[test]
+    fn configured_collision_suffix() {
+        let config = HoardConfig {
+            suffix_length: Some(1),
+            suffix_charset: Some("xy".to_string()),
+            ..HoardConfig::default()
+        };
+        let mut trove = Trove::default();
+        trove.apply_config(&config);
+        for i in 0..3 {
+            let command = HoardCmd::default()
+                .with_name("test")
+                .with_namespace("test")
+                .with_command(&format!("echo {i}"));
+            trove.add_command(command, false).unwrap();
+        }
+        let mut names: Vec<&str> = trove.commands.iter().map(|c| c.name.as_str()).collect();
+        names.sort_unstable();
+        // Suffixes are regenerated until they are unique
+        assert_eq!(vec!["test", "test-x", "test-y"], names);
+    }
+
+    #[test]
+    fn counter_and_date_collision_suffixes() {
+        let add = |trove: &mut Trove, namespace: &str, i: usize| {
+            let command = HoardCmd::default()
+                .with_name("deploy")
+                .with_namespace(namespace)
+                .with_command(&format!("make deploy-{i}"));
+            trove.add_command(command, false).unwrap();
+        };
+        let mut trove = Trove::default();
+        trove.apply_config(&HoardConfig {
+            suffix_strategy: SuffixStrategy::Counter,
+            ..HoardConfig::default()
+        });
+        for i in 0..3 {
+            add(&mut trove, "ops", i);
+        }
+        add(&mut trove, "dev", 0);
+        trove
+            .remove_command_by_id(&trove.commands[1].id.clone())
+            .unwrap();
+        add(&mut trove, "ops", 3);
+        let names: Vec<String> = trove
+            .commands
+            .iter()
+            .map(|c| format!("{}/{}", c.namespace, c.name))
+            .collect();
+        // The lowest free number of the namespace is taken
+        assert_eq!(
+            vec!["ops/deploy", "ops/deploy-3", "dev/deploy", "ops/deploy-2"],
+            names
+        );
+
+        let mut trove = Trove::default();
+        trove.apply_config(&HoardConfig {
+            suffix_strategy: SuffixStrategy::Date,
+            ..HoardConfig::default()
+        });
+        for i in 0..3 {
+            add(&mut trove, "ops", i);
+        }
+        let today = Local::now().format("%Y%m%d").to_string();
+        assert_eq!(format!("deploy-{today}"), trove.commands[1].name);
+        assert_eq!(format!("deploy-{today}-2"), trove.commands[2].name);
+    }
+
+    #[test]
+    fn suffix_strategy_config() {
+        let config: HoardConfig = serde_yaml::from_str(
+            &serde_yaml::to_string(&HoardConfig::default())
+                .unwrap()
+                .replace("suffix_strategy: random", "suffix_strategy: counter"),
+        )
+        .unwrap();
+        assert_eq!(SuffixStrategy::Counter, config.suffix_strategy);
+    }
+
+    #[test]
+    fn remove_commands_by_tag() {
+        let command = |name: &str, namespace: &str, tags: &str| {
+            HoardCmd::default()
+                .with_name(name)
+                .with_namespace(namespace)
+                .with_command("echo")
+                .with_tags_raw(tags)
+        };
+        let mut trove = Trove::from_commands(&[
+            command("old", "legacy", "deprecated"),
+            command("older", "git", "deprecated,git"),
+            command("status", "git", "git"),
+        ]);
+        assert_eq!(2, trove.remove_commands_by_tag("deprecated").unwrap());
+        assert_eq!(1, trove.commands.len());
+        assert!(!trove.namespaces.contains("legacy"));
+        assert!(trove.namespaces.contains("git"));
+        assert!(trove.remove_commands_by_tag("deprecated").is_err());
+    }
+
+    #[test]
+    fn reorder_namespace() {
+        let command = |name: &str, namespace: &str| {
+            HoardCmd::default()
+                .with_name(name)
+                .with_namespace(namespace)
+                .with_command("echo")
+        };
+        let mut trove = Trove::from_commands(&[
+            command("a", "ns"),
+            command("other", "other"),
+            command("b", "ns"),
+            command("c", "ns"),
+        ]);
+        trove.reorder_namespace("ns", &["c", "a"]).unwrap();
+        let names: Vec<&str> = trove
+            .ordered_commands()
+            .iter()
+            .map(|c| c.name.as_str())
+            .collect();
+        assert_eq!(vec!["c", "other", "a", "b"], names);
+        assert_eq!(Some(2), trove.commands[2].order);
+
+        assert!(matches!(
+            trove.reorder_namespace("ns", &["b", "other"]),
+            Err(TroveError::CommandNotFound(_))
+        ));
+        assert_eq!(Some(0), trove.commands[3].order);
+    }
+
+    #[test]
+    fn removal_prunes_empty_namespaces() {
+        let command = |name: &str| {
+            HoardCmd::default()
+                .with_name(name)
+                .with_namespace("test")
+                .with_command("echo")
+        };
+        let mut trove = Trove::default();
+        trove.add_command(command("first"), false).unwrap();
+        trove.add_command(command("second"), false).unwrap();
+        trove.remove_command("first").unwrap();
+        assert!(trove.namespaces.contains("test"));
+        trove.remove_command("second").unwrap();
+        assert!(!trove.namespaces.contains("test"));
+
+        trove.add_command(command("first"), false).unwrap();
+        trove.remove_namespace_commands("test").unwrap();
+        assert!(!trove.namespaces.contains("test"));
+    }
+
+    #[test]
+    fn validate_collects_all_errors() {
+        let command = |name: &str, namespace: &str| {
+            HoardCmd::default()
+                .with_name(name)
+                .with_namespace(namespace)
+                .with_command("echo test")
+        };
+        let mut trove = Trove::from_commands(&[
+            command("a", "test"),
+            command("", "test"),
+            command("a", "test"),
+            command("b", "other"),
+        ]);
+        trove.namespaces.remove("other");
+
+        let errors = trove.validate().unwrap_err();
+        let indices: Vec<usize> = errors.iter().map(|e| e.index).collect();
+        assert_eq!(vec![1, 2, 3], indices);
+        assert!(errors[0].reason.contains("name is empty"));
+        assert!(errors[1].reason.contains("duplicate of command 0"));
+        assert!(errors[2].reason.contains("'other'"));
+
+        trove.commands.truncate(1);
+        assert!(trove.validate().is_ok());
+    }
+
+    #[test]
+    fn paged_commands_are_sorted() {
+        let command = |name: &str, namespace: &str| {
+            HoardCmd::default()
+                .with_name(name)
+                .with_namespace(namespace)
+                .with_command("echo test")
+        };
+        let trove = Trove::from_commands(&[
+            command("b", "git"),
+            command("z", "docker"),
+            command("a", "git"),
+            command("c", "git"),
+            command("a", "docker"),
+        ]);
+        let names = |(commands, pages): (Vec<&HoardCmd>, usize)| {
+            let names: Vec<String> = commands
+                .iter()
+                .map(|c| format!("{}/{}", c.namespace, c.name))
+                .collect();
+            (names, pages)
+        };
+        assert_eq!(
+            (vec!["docker/a".to_string(), "docker/z".to_string()], 3),
+            names(trove.paged_commands(1, 2))
+        );
+        assert_eq!(
+            (vec!["git/c".to_string()], 3),
+            names(trove.paged_commands(3, 2))
+        );
+        assert!(trove.paged_commands(4, 2).0.is_empty());
+        assert_eq!(1, Trove::default().paged_commands(1, 10).1);
+    }
+
+    #[test]
+    fn sorted_commands_and_yaml() {
+        let command = |name: &str, namespace: &str| {
+            HoardCmd::default()
+                .with_name(name)
+                .with_namespace(namespace)
+                .with_command("echo test")
+        };
+        let mut trove = Trove::from_commands(&[
+            command("b", "git"),
+            command("z", "docker"),
+            command("a", "git"),
+        ]);
+        let qualified = |commands: Vec<&HoardCmd>| -> Vec<String> {
+            commands
+                .iter()
+                .map(|c| format!("{}/{}", c.namespace, c.name))
+                .collect()
+        };
+        let expected = vec!["docker/z", "git/a", "git/b"];
+        assert_eq!(expected, qualified(trove.sorted_commands()));
+        assert_eq!("b", trove.commands[0].name);
+
+        let loaded: Trove = serde_yaml::from_str(&trove.to_yaml()).unwrap();
+        assert_eq!(expected, qualified(loaded.commands.iter().collect()));
+
+        trove.sort();
+        assert_eq!(expected, qualified(trove.commands.iter().collect()));
+    }
+
+    #[test]
+    fn commands_in_namespace() {
+        let command = |name: &str, namespace: &str| {
+            HoardCmd::default()
+                .with_name(name)
+                .with_namespace(namespace)
+                .with_command("echo test")
+        };
+        let mut trove = Trove::from_commands(&[
+            command("push", "git"),
+            command("ps", "docker"),
+            command("add", "git"),
+        ]);
+        trove.set_namespace_alias("g", "git").unwrap();
+        let names = |commands: Vec<&HoardCmd>| -> Vec<String> {
+            commands.iter().map(|c| c.name.clone()).collect()
+        };
+        assert_eq!(
+            vec!["add", "push"],
+            names(trove.commands_in_namespace("git"))
+        );
+        assert_eq!(vec!["add", "push"], names(trove.commands_in_namespace("g")));
+        assert!(trove.commands_in_namespace("unknown").is_empty());
+    }
+
+    #[test]
+    fn namespace_stats() {
+        let command = |name: &str, namespace: &str| {
+            HoardCmd::default()
+                .with_name(name)
+                .with_namespace(namespace)
+                .with_command("echo test")
+        };
+        let mut trove = Trove::from_commands(&[
+            command("a", "git"),
+            command("b", "k8s"),
+            command("c", "docker"),
+            command("d", "k8s"),
+        ]);
+        trove.namespaces.insert("stale".to_string());
+        assert_eq!(
+            vec![
+                ("k8s".to_string(), 2),
+                ("docker".to_string(), 1),
+                ("git".to_string(), 1)
+            ],
+            trove.namespace_stats()
+        );
+        assert_eq!(4, trove.total_commands());
+    }
+
+    #[test]
+    fn rename_namespace() {
+        let command = |name: &str, namespace: &str| {
+            HoardCmd::default()
+                .with_name(name)
+                .with_namespace(namespace)
+                .with_command(&format!("kubectl {name}"))
+        };
+        let mut trove = Trove::from_commands(&[
+            command("pods", "k8s"),
+            command("nodes", "k8s"),
+            command("pods", "kubernetes"),
+            command("status", "git"),
+        ]);
+        trove.set_namespace_alias("kube", "k8s").unwrap();
+
+        assert_eq!(2, trove.rename_namespace("kube", "kubernetes").unwrap());
+        assert!(!trove.has_namespace("k8s"));
+        assert!(!trove.namespaces.contains("k8s"));
+        assert_eq!("kubernetes", trove.resolve_namespace_alias("kube"));
+        let moved: Vec<&HoardCmd> = trove
+            .commands
+            .iter()
+            .filter(|c| c.namespace == "kubernetes")
+            .collect();
+        assert_eq!(3, moved.len());
+        // The colliding command got a suffix
+        assert!(moved[0].name.starts_with("pods") && moved[0].name != "pods");
+
+        assert!(trove.rename_namespace("k8s", "other").is_err());
+    }
+
+    #[test]
+    fn add_command_strict() {
+        let command = HoardCmd::default()
+            .with_name("deploy")
+            .with_namespace("ops")
+            .with_command("make deploy");
+        let mut trove = Trove::default();
+        trove.add_command_strict(command.clone()).unwrap();
+
+        let colliding = command.clone().with_command("make deploy-all");
+        match trove.add_command_strict(colliding) {
+            Err(TroveError::CommandCollision {
+                namespace,
+                name,
+                command,
+            }) => {
+                assert_eq!(("ops", "deploy"), (namespace.as_str(), name.as_str()));
+                assert_eq!("make deploy", command);
+            }
+            other => panic!("expected a collision, got {other:?}"),
+        }
+        assert!(trove.add_command_strict(command).is_err());
+        assert!(matches!(
+            trove.add_command_strict(HoardCmd::default().with_name("empty")),
+            Err(TroveError::Invalid(_))
+        ));
+        assert_eq!(1, trove.commands.len());
+        assert_eq!("deploy", trove.commands[0].name);
+    }
+
+    #[test]
+    fn filter_by_tags() {
+        let command = |name: &str, tags: &str| {
+            HoardCmd::default()
+                .with_name(name)
+                .with_namespace("test")
+                .with_command("echo test")
+                .with_tags_raw(tags)
+        };
+        let trove = Trove::from_commands(&[
+            command("both", "Prod,k8s"),
+            command("prod", "prod"),
+            command("none", ""),
+        ]);
+        let names = |commands: Vec<&HoardCmd>| -> Vec<String> {
+            commands.iter().map(|c| c.name.clone()).collect()
+        };
+        let tags = vec!["PROD".to_string(), "k8s".to_string()];
+        assert_eq!(vec!["both"], names(trove.filter_by_tags(&tags, true)));
+        assert_eq!(
+            vec!["both", "prod"],
+            names(trove.filter_by_tags(&tags, false))
+        );
+        assert_eq!(3, trove.filter_by_tags(&[], true).len());
+    }
+
+    #[test]
+    fn all_tags_counts_ignoring_case() {
+        let command = |name: &str, tags: &str| {
+            HoardCmd::default()
+                .with_name(name)
+                .with_namespace("test")
+                .with_command("echo test")
+                .with_tags_raw(tags)
+        };
+        let trove = Trove::from_commands(&[
+            command("a", "k8s,Prod"),
+            command("b", "prod,PROD"),
+            command("c", "dev,K8S,prod"),
+            command("d", ""),
+        ]);
+        assert_eq!(
+            vec![
+                ("Prod".to_string(), 3),
+                ("k8s".to_string(), 2),
+                ("dev".to_string(), 1)
+            ],
+            trove.all_tags()
+        );
+    }
+
+    #[test]
+    fn save_trove_file_atomically() {
+        let tmp_dir = tempfile::tempdir().unwrap();
+        let path = tmp_dir.path().join("trove.yml");
+        let trove = Trove::from_commands(&[HoardCmd::default()
+            .with_name("test")
+            .with_namespace("test")
+            .with_command("echo test")]);
+        trove.save_trove_file(&path).unwrap();
+        Trove::default().save_trove_file(&path).unwrap();
+        assert!(Trove::try_load_trove_file(&Some(path)).unwrap().is_empty());
+        // Only the trove file is left, no temporary files
+        assert_eq!(1, fs::read_dir(tmp_dir.path()).unwrap().count());
+
+        // A failed save keeps the previous file
+        let dir_path = tmp_dir.path().join("dir");
+        fs::create_dir(&dir_path).unwrap();
+        assert!(trove.save_trove_file(&dir_path).is_err());
+        assert!(dir_path.is_dir());
+        assert_eq!(2, fs::read_dir(tmp_dir.path()).unwrap().count());
+        assert!(trove
+            .save_trove_file(&tmp_dir.path().join("missing").join("trove.yml"))
+            .is_err());
+    }
+
+    #[test]
+    fn reader_and_writer_round_trip() {
+        let trove = Trove::from_commands(&[HoardCmd::default()
+            .with_name("multi")
+            .with_namespace("test")
+            .with_command("echo a\necho b")
+            .with_tags_raw("a,a")]);
+        let mut written = Vec::new();
+        trove.write_to_writer(&mut written).unwrap();
+        assert_eq!(trove.to_yaml().as_bytes(), written.as_slice());
+
+        let loaded = Trove::load_trove_from_reader(written.as_slice()).unwrap();
+        assert_eq!(vec!["a"], loaded.commands[0].tags);
+        assert!(loaded.namespaces.contains("test"));
+        assert!(Trove::load_trove_from_reader(&b"commands: ["[..]).is_err());
+    }
+
+    #[test]
+    fn pick_command_without_parameter_tokens() {
+        let trove = Trove::from_commands(&[
+            HoardCmd::default()
+                .with_name("plain")
+                .with_namespace("test")
+                .with_command("echo plain"),
+            HoardCmd::default()
+                .with_name("unterminated")
+                .with_namespace("test")
+                .with_command("echo #value"),
+        ]);
+        let config = HoardConfig {
+            parameter_token: None,
+            parameter_ending_token: None,
+            ..HoardConfig::default()
+        };
+        assert_eq!(
+            "echo plain",
+            trove.pick_command(&config, "plain").unwrap().command
+        );
+        assert!(trove.pick_command(&config, "unterminated").is_err());
+        assert!(trove.pick_command(&config, "missing").is_err());
+    }
+
+    #[test]
+    fn try_to_yaml_returns_errors() {
+        let mut command = HoardCmd::default()
+            .with_name("test")
+            .with_namespace("test")
+            .with_command("echo test");
+        let trove = Trove::from_commands(&[command.clone()]);
+        assert_eq!(trove.to_yaml(), trove.try_to_yaml().unwrap());
+
+        // Times before the unix epoch can not be serialized
+        command.created = std::time::UNIX_EPOCH - std::time::Duration::from_secs(1);
+        let trove = Trove::from_commands(&[command]);
+        assert!(matches!(trove.try_to_yaml(), Err(TroveError::Yaml(_))));
+        assert_eq!("", trove.to_yaml());
+    }
+
+    #[test]
+    fn try_to_json_returns_errors() {
+        let mut command = HoardCmd::default()
+            .with_name("test")
+            .with_namespace("test")
+            .with_command("echo test");
+        let trove = Trove::from_commands(&[command.clone()]);
+        assert_eq!(trove.to_json(), trove.try_to_json().unwrap());
+
+        // Times before the unix epoch can not be serialized
+        command.created = std::time::UNIX_EPOCH - std::time::Duration::from_secs(1);
+        let trove = Trove::from_commands(&[command]);
+        assert!(matches!(trove.try_to_json(), Err(TroveError::Json(_))));
+        assert_eq!("", trove.to_json());
+    }
+
+    #[test]
+    fn add_commands_in_one_pass() {
+        let command = |name: &str, namespace: &str, command: &str| {
+            HoardCmd::default()
+                .with_name(name)
+                .with_namespace(namespace)
+                .with_command(command)
+        };
+        let mut trove = Trove::from_commands(&[command("st", "git", "git status")]);
+        let report = trove
+            .add_commands(
+                vec![
+                    command("st", "git", "git status"),
+                    command("st", "git", "git status -sb"),
+                    command("log", "git", "git log"),
+                    command("log", "git", "git log --oneline"),
+                ],
+                false,
+            )
+            .unwrap();
+        assert_eq!(vec!["git/st"], report.skipped);
+        assert_eq!(vec!["git/log"], report.added);
+        assert_eq!(2, report.renamed.len());
+        assert_eq!(4, trove.commands.len());
+
+        let report = trove
+            .add_commands(vec![command("st", "git", "git status -s")], true)
+            .unwrap();
+        assert_eq!(vec!["git/st"], report.overwritten);
+        assert_eq!("git status -s", trove.commands[0].command);
+
+        let invalid = vec![command("ok", "git", "ls"), command("", "git", "ls")];
+        assert!(matches!(
+            trove.add_commands(invalid, false),
+            Err(TroveError::Invalid(_))
+        ));
+        assert_eq!(4, trove.commands.len());
+    }
+
+    /// Add `count` commands with `add_commands` and with an `add_command` loop, checking both give the same trove
+    ///
+    /// Returns how long the loop and `add_commands` took
+    fn time_adding_commands(count: usize) -> (std::time::Duration, std::time::Duration) {
+        let commands: Vec<HoardCmd> = (0..count)
+            .map(|i| {
+                HoardCmd::default()
+                    .with_name(&format!("command{i}"))
+                    .with_namespace(&format!("namespace{}", i % 10))
+                    .with_command("echo test")
+            })
+            .collect();
+
+        let start = std::time::Instant::now();
+        let mut looped = Trove::default();
+        for command in commands.clone() {
+            looped.add_command(command, false).unwrap();
+        }
+        let loop_time = start.elapsed();
+
+        let start = std::time::Instant::now();
+        let mut batched = Trove::default();
+        batched.add_commands(commands, false).unwrap();
+        let batch_time = start.elapsed();
+
+        assert_eq!(looped.commands, batched.commands);
+        assert_eq!(looped.namespaces, batched.namespaces);
+        (loop_time, batch_time)
+    }
+
+    #[test]
+    fn add_commands_matches_add_command_loop() {
+        time_adding_commands(300);
+    }
+
+    #[test]
+    #[ignore = "benchmark, depends on the load of the machine"]
+    fn bench_add_commands_against_add_command_loop() {
+        let (loop_time, batch_time) = time_adding_commands(3000);
+        assert!(
+            batch_time < loop_time,
+            "add_commands took {batch_time:?}, the add_command loop took {loop_time:?}"
+        );
+    }
 }
@@ -0,0 +1,291 @@
+use std::io::{self, Write};
+
+/// Indentation of block scalar content relative to its key
+const BLOCK_INDENT: usize = 2;
+
+/// Render `value` as lines of a literal block scalar (`|`) indented by `indent` spaces
+///
+/// Returns `None` if `value` is better kept as a quoted string, e.g. because it is a single line or contains control characters
+fn literal_block(value: &str, indent: usize) -> Option<(String, Vec<String>)> {
+    if !value.contains('\n')
+        || value.trim().is_empty()
+        || value
+            .chars()
+            .any(|c| c.is_control() && c != '\n' && c != '\t')
+    {
+        return None;
+    }
+    let content = value.trim_end_matches('\n');
+    let trailing_newlines = value.len() - content.len();
+    let chomping = match trailing_newlines {
+        0 => "-",
+        1 => "",
+        _ => "+",
+    };
+    let lines: Vec<&str> = content.split('\n').collect();
+    // Leading whitespace of the first lines would be taken as indentation (tabs are not allowed there), so the indentation has to be explicit
+    let needs_indicator = lines
+        .iter()
+        .take_while(|line| line.trim().is_empty())
+        .chain(lines.iter().find(|line| !line.trim().is_empty()))
+        .any(|line| line.starts_with(char::is_whitespace));
+    let header = if needs_indicator {
+        format!("|{BLOCK_INDENT}{chomping}")
+    } else {
+        format!("|{chomping}")
+    };
+    let padding = " ".repeat(indent);
+    let mut block: Vec<String> = lines
+        .iter()
+        .map(|line| {
+            if line.is_empty() {
+                String::new()
+            } else {
+                format!("{padding}{line}")
+            }
+        })
+        .collect();
+    block.extend((1..trailing_newlines).map(|_| String::new()));
+    Some((header, block))
+}
+
+/// Render `value` as lines of a folded block scalar (`>-`) indented by `indent` spaces, wrapped at `width` columns
+///
+/// Returns `None` if `value` can not be folded without changing it, e.g. because it contains repeated whitespace
+fn folded_block(value: &str, indent: usize, width: usize) -> Option<(String, Vec<String>)> {
+    if value.is_empty()
+        || value.trim() != value
+        || value.contains("  ")
+        || value.chars().any(char::is_control)
+    {
+        return None;
+    }
+    let padding = " ".repeat(indent);
+    let mut block: Vec<String> = Vec::new();
+    let mut line = String::new();
+    for word in value.split(' ') {
+        if !line.is_empty() && indent + line.len() + 1 + word.len() > width {
+            block.push(format!("{padding}{line}"));
+            line.clear();
+        }
+        if !line.is_empty() {
+            line.push(' ');
+        }
+        line.push_str(word);
+    }
+    block.push(format!("{padding}{line}"));
+    if block.len() < 2 {
+        return None;
+    }
+    Some((">-".to_string(), block))
+}
+
+/// Formatting options of yaml written by hoard
+///
+/// - `block_scalars`: Write multi-line strings as literal block scalars (`|`) instead of quoted single lines
+/// - `line_width`: Fold single-line strings longer than this many columns into folded block scalars (`>-`), `None` never wraps lines
+///
+/// The default is used by `Trove::to_yaml`
+#[allow(dead_code)]
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub struct YamlStyle {
+    pub block_scalars: bool,
+    pub line_width: Option<usize>,
+}
+
+impl Default for YamlStyle {
+    fn default() -> Self {
+        Self {
+            block_scalars: true,
+            line_width: None,
+        }
+    }
+}
+
+/// Rewrite a single line of yaml serialized by `serde_yaml` according to `style` and append it to `output`
+fn style_line(line: &str, keys: &[&str], style: YamlStyle, output: &mut String) {
+    let mut key_column = line.len() - line.trim_start().len();
+    let mut rest = line.trim_start();
+    while let Some(stripped) = rest.strip_prefix("- ") {
+        key_column += 2;
+        rest = stripped;
+    }
+    let block = keys.iter().find_map(|key| {
+        let raw_value = rest.strip_prefix(key)?.strip_prefix(": ")?;
+        let value: String = serde_yaml::from_str(raw_value).ok()?;
+        let indent = key_column + BLOCK_INDENT;
+        let block = if style.block_scalars && value.contains('\n') {
+            literal_block(&value, indent)
+        } else {
+            style
+                .line_width
+                .filter(|width| line.len() > *width)
+                .and_then(|width| folded_block(&value, indent, width))
+        };
+        block.map(|(header, block)| (key, header, block))
+    });
+    if let Some((key, header, block)) = block {
+        output.push_str(&line[..line.len() - rest.len()]);
+        output.push_str(key);
+        output.push_str(": ");
+        output.push_str(&header);
+        output.push('\n');
+        for block_line in block {
+            output.push_str(&block_line);
+            output.push('\n');
+        }
+    } else {
+        output.push_str(line);
+        output.push('\n');
+    }
+}
+
+/// Rewrite string values of `keys` in yaml serialized by `serde_yaml` according to `style`
+///
+/// `serde_yaml` writes multi-line strings as a single double quoted line with escaped newlines,
+/// which is hard to read and edit by hand. The rewritten yaml deserializes to the same values.
+pub fn with_style(yaml: &str, keys: &[&str], style: YamlStyle) -> String {
+    let mut output = String::with_capacity(yaml.len());
+    for line in yaml.lines() {
+        style_line(line, keys, style, &mut output);
+    }
+    output
+}
+
+/// A writer applying `with_style` to the yaml written through it, one line at a time
+///
+/// Only the current line is buffered, so large documents can be streamed to `inner`.
+/// Call `finish` after writing to flush the last line
+pub struct StyledWriter<'a, W: Write> {
+    inner: W,
+    keys: &'a [&'a str],
+    style: YamlStyle,
+    line: Vec<u8>,
+}
+
+impl<'a, W: Write> StyledWriter<'a, W> {
+    pub const fn new(inner: W, keys: &'a [&'a str], style: YamlStyle) -> Self {
+        Self {
+            inner,
+            keys,
+            style,
+            line: Vec::new(),
+        }
+    }
+
+    fn write_line(&mut self) -> io::Result<()> {
+        let line = String::from_utf8(std::mem::take(&mut self.line))
+            .map_err(|e| io::Error::new(io::ErrorKind::InvalidData, e))?;
+        let mut styled = String::new();
+        style_line(
+            line.trim_end_matches('\r'),
+            self.keys,
+            self.style,
+            &mut styled,
+        );
+        self.inner.write_all(styled.as_bytes())
+    }
+
+    /// Write the last, unterminated line and flush the inner writer
+    pub fn finish(mut self) -> io::Result<W> {
+        if !self.line.is_empty() {
+            self.write_line()?;
+        }
+        self.inner.flush()?;
+        Ok(self.inner)
+    }
+}
+
+impl<W: Write> Write for StyledWriter<'_, W> {
+    fn write(&mut self, buf: &[u8]) -> io::Result<usize> {
+        for &b in buf {
+            if b == b'\n' {
+                self.write_line()?;
+            } else {
+                self.line.push(b);
+            }
+        }
+        Ok(buf.len())
+    }
+
+    fn flush(&mut self) -> io::Result<()> {
+        self.inner.flush()
+    }
+}
+
+#[cfg(test)]
+mod test_commands {
+    use super::*;
+    use std::collections::BTreeMap;
+
+    fn round_trip(value: &str) -> String {
+        let map = BTreeMap::from([("command", value)]);
+        let yaml = with_style(
+            &serde_yaml::to_string(&map).unwrap(),
+            &["command"],
+            YamlStyle::default(),
+        );
+        let loaded: BTreeMap<String, String> = serde_yaml::from_str(&yaml).unwrap();
+        assert_eq!(value, loaded["command"], "yaml was:\n{yaml}");
+        yaml
+    }
+
+    #[test]
+    fn multi_line_values_become_block_scalars() {
+        let yaml = round_trip("echo a\necho b");
+        assert!(yaml.contains("command: |-\n  echo a\n  echo b\n"));
+        round_trip("echo a  \n\n\techo b\n");
+        round_trip("echo a\n\n\n");
+        round_trip("  indented\nnot indented");
+        round_trip("\n  after empty line");
+        assert!(round_trip("\techo a\necho b").contains("command: |2-\n"));
+        round_trip("\n\tafter empty line");
+    }
+
+    #[test]
+    fn other_values_stay_quoted() {
+        assert!(round_trip("echo a").contains("command: echo a"));
+        assert!(round_trip("echo \u{1}\nb").contains("\\u0001"));
+        assert!(round_trip("\n\n").contains("\"\\n\\n\""));
+    }
+
+    #[test]
+    fn style_options() {
+        let value = "a long description that does not fit into a narrow line width";
+        let map = BTreeMap::from([("description", value), ("command", "echo a\necho b")]);
+        let yaml = serde_yaml::to_string(&map).unwrap();
+        let keys = ["command", "description"];
+
+        let styled = with_style(&yaml, &keys, YamlStyle::default());
+        assert!(styled.contains("command: |-\n"));
+        assert!(styled.contains(&format!("description: {value}")));
+
+        let style = YamlStyle {
+            block_scalars: false,
+            line_width: Some(30),
+        };
+        let styled = with_style(&yaml, &keys, style);
+        assert!(styled.contains("description: >-\n"));
+        assert!(styled.lines().all(|line| line.len() <= 30));
+        assert!(styled.contains("command: \"echo a\\necho b\""));
+        let loaded: BTreeMap<String, String> = serde_yaml::from_str(&styled).unwrap();
+        assert_eq!(value, loaded["description"]);
+        assert_eq!("echo a\necho b", loaded["command"]);
+    }
+
+    #[test]
+    fn styled_writer_matches_with_style() {
+        let map = BTreeMap::from([("command", "echo a\necho b"), ("name", "multi")]);
+        let yaml = serde_yaml::to_string(&map).unwrap();
+        let mut writer = StyledWriter::new(Vec::new(), &["command"], YamlStyle::default());
+        // Split writes in the middle of lines
+        for chunk in yaml.as_bytes().chunks(3) {
+            writer.write_all(chunk).unwrap();
+        }
+        let written = String::from_utf8(writer.finish().unwrap()).unwrap();
+        assert_eq!(
+            with_style(&yaml, &["command"], YamlStyle::default()),
+            written
+        );
+    }
+}
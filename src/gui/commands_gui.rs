@@ -157,6 +157,7 @@ pub fn run(trove: &mut Trove, config: &HoardConfig) -> Result<Option<HoardCmd>>
     if openai_api_key.is_empty() {
         openai_api_key = config.gpt_api_key.clone().unwrap_or_default();
     }
+    let (parameter_token, parameter_ending_token) = config.parameter_tokens();
 
     let mut app_state = State {
         input: String::new(),
@@ -170,8 +171,8 @@ pub fn run(trove: &mut Trove, config: &HoardConfig) -> Result<Option<HoardCmd>>
         edit_selection: EditSelection::Command,
         new_command: None,
         string_to_edit: String::new(),
-        parameter_token: config.parameter_token.as_ref().unwrap().clone(),
-        parameter_ending_token: config.parameter_ending_token.as_ref().unwrap().clone(),
+        parameter_token,
+        parameter_ending_token,
 
         selected_command: None,
         provided_parameter_count: 0,
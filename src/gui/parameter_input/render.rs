@@ -63,8 +63,8 @@ pub fn draw(
             .command
             .as_str();
 
-        let token = config.parameter_token.as_ref().unwrap().as_str();
-        let ending_token = config.parameter_ending_token.as_ref().unwrap().as_str();
+        let (token, ending_token) = config.parameter_tokens();
+        let (token, ending_token) = (token.as_str(), ending_token.as_str());
         // Named parameter ending with a space
         let named_token = string_find_next(command_text, token, " ");
         // Named parameter ending with ending token. If ending token is not used, `full_named_token` is an empty string
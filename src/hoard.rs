@@ -133,16 +133,16 @@ impl Hoard {
     ) {
         let trove_namespaces = self.trove.namespaces();
         //trove_namespaces.push(&default_ns_clone);
+        let (token, ending_token) = self.config.parameter_tokens();
         let new_command = HoardCmd::default()
-            .with_command_string_input(
-                command,
-                &self.config.parameter_token.clone().unwrap(),
-                &self.config.parameter_ending_token.clone().unwrap(),
-            )
+            .with_command_string_input(command, &token, &ending_token)
             .with_namespace_input(&trove_namespaces)
             .with_name_input(name, &self.trove)
             .with_description_input(description.unwrap_or_default())
             .with_tags_input(tags);
+        for warning in new_command.shell_lint() {
+            eprintln!("Warning: {warning}");
+        }
         let _ = self.trove.add_command(new_command, true);
         self.save_trove(None);
     }
@@ -155,13 +155,13 @@ impl Hoard {
     ) -> Option<String> {
         if self.trove.is_empty() {
             println!("No command hoarded.\nRun [ hoard new ] first to hoard a command.");
-        } else if is_simple {
-            self.trove.print_trove();
         } else if is_structured {
             // Return list of commands in json format, filtered by `filter`
             let query_string: String = filter.unwrap_or_default();
             let filtered_trove = query_trove(&self.trove, &query_string);
-            return Some(filtered_trove.to_yaml());
+            return Some(filtered_trove.to_list_json());
+        } else if is_simple {
+            self.trove.print_trove(&self.config);
         } else {
             match commands_gui::run(&mut self.trove, &self.config) {
                 Ok(selected_command) => {
@@ -228,9 +228,13 @@ impl Hoard {
             },
             Err(err) => {
                 if err == ParseError::RelativeUrlWithoutBase {
-                    let imported_trove = Trove::load_trove_file(&Some(PathBuf::from(path)));
-                    self.trove.merge_trove(&imported_trove);
-                    self.save_trove(None);
+                    match Trove::try_load_trove_file(&Some(PathBuf::from(path))) {
+                        Ok(imported_trove) => {
+                            self.trove.merge_trove(&imported_trove);
+                            self.save_trove(None);
+                        }
+                        Err(e) => eprintln!("ERROR: Could not import trove file: {e}"),
+                    }
                 } else {
                     eprintln!("Not a valid URL or file path");
                 }
@@ -279,7 +283,9 @@ impl Hoard {
                 let _ = trove_for_export.add_command(command.clone(), true);
             }
 
-            trove_for_export.save_trove_file(&target_path);
+            if let Err(e) = trove_for_export.save_trove_file(&target_path) {
+                eprintln!("ERROR: Unable to export trove file: {e}");
+            }
         } else {
             println!("No valid path with filename provided.");
         }
@@ -301,12 +307,10 @@ impl Hoard {
         match command_to_edit {
             Ok(c) => {
                 println!("{}", c.command);
+                let (token, ending_token) = self.config.parameter_tokens();
                 let new_command = HoardCmd::default()
-                    .with_command_string_input(
-                        Some(c.command.clone()),
-                        &self.config.parameter_token.clone().unwrap(),
-                        &self.config.parameter_ending_token.clone().unwrap(),
-                    )
+                    .with_id(&c.id)
+                    .with_command_string_input(Some(c.command.clone()), &token, &ending_token)
                     .with_name_input(Some(c.name.clone()), &self.trove)
                     .with_description_input(c.description.clone())
                     .with_tags_input(Some(c.get_tags_as_string()))
@@ -333,13 +337,25 @@ impl Hoard {
     }
 
     pub fn load_trove(&mut self) -> &mut Self {
-        self.trove = Trove::load_trove_file(&self.config.trove_path);
+        match Trove::try_load_trove_file(&self.config.trove_path) {
+            Ok(trove) => self.trove = trove,
+            Err(e) => {
+                // Continuing with an empty trove would overwrite the trove file on the next save
+                eprintln!("ERROR: Unable to load trove file: {e}");
+                std::process::exit(1);
+            }
+        }
+        self.trove.apply_config(&self.config);
+        self.trove.dedupe_if_configured(&self.config);
         self
     }
 
     pub fn save_trove(&self, path: Option<&Path>) {
         let path_to_save = path.unwrap_or_else(|| self.config.trove_path.as_ref().unwrap());
-        self.trove.save_trove_file(path_to_save);
+        if let Err(e) = self.trove.save_trove_file(path_to_save) {
+            eprintln!("ERROR: Unable to save trove file: {e}");
+            std::process::exit(1);
+        }
     }
 
     fn save_backup_trove(&self, path: Option<&Path>) {
@@ -349,7 +365,10 @@ impl Hoard {
         );
         let backup_trove_path = PathBuf::from_str(&backup_trove_path_str).ok().unwrap();
         let path_to_save = path.unwrap_or(&backup_trove_path);
-        self.trove.save_trove_file(path_to_save);
+        if let Err(e) = self.trove.save_trove_file(path_to_save) {
+            eprintln!("ERROR: Unable to save backup trove file: {e}");
+            std::process::exit(1);
+        }
     }
 
     fn revert_trove(&self) {
@@ -16,6 +16,7 @@ mod filter;
 mod gpt;
 mod gui;
 mod hoard;
+mod serve;
 mod sync_models;
 mod util;
 use hoard::Hoard;
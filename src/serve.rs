@@ -0,0 +1,361 @@
+use log::{info, warn};
+use serde::Serialize;
+use std::io::{BufRead, BufReader, Read, Write};
+use std::net::{TcpListener, TcpStream, ToSocketAddrs};
+use std::path::PathBuf;
+use std::sync::atomic::{AtomicBool, Ordering};
+use std::sync::{Arc, RwLock};
+use std::time::{Duration, SystemTime};
+use std::{fs, io, thread};
+
+use crate::core::error::TroveError;
+use crate::core::trove::Trove;
+use crate::core::HoardCmd;
+
+/// Maximum size of the request line and headers of a request, longer requests are rejected
+const MAX_REQUEST: u64 = 8 * 1024;
+
+/// Time a client may take to send its request or to receive the response before the connection is dropped
+const CONNECTION_TIMEOUT: Duration = Duration::from_secs(5);
+
+#[derive(Serialize)]
+struct ErrorBody {
+    error: String,
+}
+
+/// Status line and JSON body of a response
+type Response = (&'static str, String);
+
+fn json<T: Serialize>(status: &'static str, body: &T) -> Response {
+    let body = serde_json::to_string(body).unwrap_or_else(|_| "null".to_string());
+    (status, body)
+}
+
+fn error(status: &'static str, message: &str) -> Response {
+    json(
+        status,
+        &ErrorBody {
+            error: message.to_string(),
+        },
+    )
+}
+
+/// Answer a single request of the read-only trove API
+///
+/// - `GET /commands`: all commands
+/// - `GET /commands?namespace=x`: all commands of namespace `x`, namespace aliases are resolved
+/// - `GET /search?q=...`: all commands matching a query of the hoard filter language, see `Trove::query`
+fn respond(trove: &Trove, method: &str, target: &str) -> Response {
+    if method != "GET" {
+        return error("405 Method Not Allowed", "only GET requests are supported");
+    }
+    let (path, query) = target.split_once('?').unwrap_or((target, ""));
+    let param = |key: &str| {
+        url::form_urlencoded::parse(query.as_bytes())
+            .find(|(k, _)| k == key)
+            .map(|(_, v)| v.into_owned())
+    };
+    match path {
+        "/commands" => {
+            let commands: Vec<&HoardCmd> = param("namespace").map_or_else(
+                || trove.commands.iter().collect(),
+                |namespace| {
+                    let namespace = trove.resolve_namespace_alias(&namespace);
+                    trove
+                        .commands
+                        .iter()
+                        .filter(|c| c.namespace == namespace)
+                        .collect()
+                },
+            );
+            json("200 OK", &commands)
+        }
+        "/search" => match trove.query(&param("q").unwrap_or_default()) {
+            Ok(commands) => json("200 OK", &commands),
+            Err(e) => error("400 Bad Request", &e.to_string()),
+        },
+        _ => error("404 Not Found", "unknown endpoint"),
+    }
+}
+
+/// Read the request line and headers of a HTTP request from `stream` and write the response back
+///
+/// Requests larger than `MAX_REQUEST` and requests whose `Host` header is not one of `hosts` are rejected,
+/// the latter so websites can not read the trove through DNS rebinding
+fn handle_connection<S: Read + Write>(
+    mut stream: S,
+    trove: &RwLock<Trove>,
+    hosts: &[String],
+) -> Result<(), TroveError> {
+    let mut request_line = String::new();
+    let mut host = None;
+    let complete = {
+        let mut reader = BufReader::new((&mut stream).take(MAX_REQUEST));
+        reader.read_line(&mut request_line)?;
+        // Read the headers up to the empty line, requests bodies are not supported
+        let mut header = String::new();
+        let mut complete = false;
+        while reader.read_line(&mut header)? > 0 {
+            if header.trim_end().is_empty() {
+                complete = true;
+                break;
+            }
+            if let Some((name, value)) = header.split_once(':') {
+                if name.eq_ignore_ascii_case("host") {
+                    host = Some(value.trim().to_string());
+                }
+            }
+            header.clear();
+        }
+        complete
+    };
+    let mut parts = request_line.split_whitespace();
+    let (status, body) = match (parts.next(), parts.next()) {
+        _ if !complete => error(
+            "431 Request Header Fields Too Large",
+            "incomplete or too large request",
+        ),
+        _ if !host.is_some_and(|host| hosts.contains(&host)) => {
+            error("403 Forbidden", "unexpected Host header")
+        }
+        (Some(method), Some(target)) => trove.read().map_or_else(
+            |_| error("500 Internal Server Error", "trove is unavailable"),
+            |trove| respond(&trove, method, target),
+        ),
+        _ => error("400 Bad Request", "malformed request line"),
+    };
+    write!(
+        stream,
+        "HTTP/1.1 {status}\r\nContent-Type: application/json\r\nContent-Length: {}\r\nConnection: close\r\n\r\n{body}",
+        body.len()
+    )?;
+    stream.flush()?;
+    Ok(())
+}
+
+/// Answer a request like `handle_connection`, dropping clients that are slower than `CONNECTION_TIMEOUT`
+fn handle_tcp_connection(
+    stream: &TcpStream,
+    trove: &RwLock<Trove>,
+    hosts: &[String],
+) -> Result<(), TroveError> {
+    stream.set_read_timeout(Some(CONNECTION_TIMEOUT))?;
+    stream.set_write_timeout(Some(CONNECTION_TIMEOUT))?;
+    handle_connection(stream, trove, hosts)
+}
+
+#[allow(dead_code, clippy::needless_pass_by_value)]
+/// Serve the trove collection as a read-only JSON API on `addr`
+///
+/// Only loopback addresses (e.g. `127.0.0.1:8080` or `localhost:8080`) are accepted, so the trove is never exposed to the network.
+/// Requests are answered one after another until the listener fails, clients taking longer than `CONNECTION_TIMEOUT` are dropped.
+/// Only requests addressed to `addr` or the bound socket address in their `Host` header are answered.
+/// Use `watch_trove_file` to keep the served trove in sync with the trove file
+pub fn serve(trove: Arc<RwLock<Trove>>, addr: &str) -> Result<(), TroveError> {
+    let addresses: Vec<_> = addr.to_socket_addrs()?.collect();
+    if addresses.is_empty() || addresses.iter().any(|a| !a.ip().is_loopback()) {
+        return Err(TroveError::Io(io::Error::new(
+            io::ErrorKind::PermissionDenied,
+            format!("refusing to serve the trove on non-loopback address '{addr}'"),
+        )));
+    }
+    let listener = TcpListener::bind(addresses.as_slice())?;
+    let local_addr = listener.local_addr()?;
+    info!("Serving trove on {local_addr}");
+    let host = addr.rsplit_once(':').map_or(addr, |(host, _)| host);
+    let hosts = vec![
+        local_addr.to_string(),
+        format!("{host}:{}", local_addr.port()),
+    ];
+    for stream in listener.incoming() {
+        match stream {
+            Ok(stream) => {
+                if let Err(e) = handle_tcp_connection(&stream, &trove, &hosts) {
+                    warn!("Failed to answer request: {e}");
+                }
+            }
+            Err(e) => warn!("Failed to accept connection: {e}"),
+        }
+    }
+    Ok(())
+}
+
+#[allow(dead_code)]
+/// Reload `trove` from the trove file at `path` whenever its modification time changes
+///
+/// The file is checked every `interval` on a background thread and loaded like `Trove::try_load_trove_file`.
+/// The options and event sink of the served trove are kept.
+/// If the file cannot be parsed, the previously loaded trove is kept.
+/// The thread finishes once `stop` is set
+pub fn watch_trove_file(
+    trove: Arc<RwLock<Trove>>,
+    path: PathBuf,
+    interval: Duration,
+    stop: Arc<AtomicBool>,
+) -> thread::JoinHandle<()> {
+    let modified = |path: &PathBuf| fs::metadata(path).and_then(|m| m.modified()).ok();
+    // Taken before spawning, so changes right after the call are not missed
+    let mut last_modified: Option<SystemTime> = modified(&path);
+    thread::spawn(move || loop {
+        thread::sleep(interval);
+        if stop.load(Ordering::Relaxed) {
+            break;
+        }
+        let current = modified(&path);
+        if current == last_modified {
+            continue;
+        }
+        last_modified = current;
+        let reloaded = Trove::try_load_trove_file(&Some(path.clone()));
+        match (reloaded, trove.write()) {
+            (Ok(mut reloaded), Ok(mut trove)) => {
+                info!("Reloaded trove from {}", path.display());
+                reloaded.options = trove.options.clone();
+                reloaded.events = trove.events.clone();
+                *trove = reloaded;
+            }
+            (Err(e), _) => warn!("Could not reload trove from {}: {e}", path.display()),
+            (_, Err(_)) => warn!("Could not reload trove, the lock is poisoned"),
+        }
+    })
+}
+
+#[cfg(test)]
+mod test_commands {
+    use super::*;
+
+    fn trove() -> Trove {
+        let commands = vec![
+            HoardCmd::default()
+                .with_name("pods")
+                .with_namespace("k8s")
+                .with_command("kubectl get pods"),
+            HoardCmd::default()
+                .with_name("status")
+                .with_namespace("git")
+                .with_command("git status"),
+        ];
+        Trove::from_commands(&commands)
+    }
+
+    #[test]
+    fn endpoints() {
+        let mut trove = trove();
+        trove.set_namespace_alias("kube", "k8s").unwrap();
+
+        let (status, body) = respond(&trove, "GET", "/commands");
+        assert_eq!("200 OK", status);
+        assert_eq!(
+            2,
+            serde_json::from_str::<Vec<HoardCmd>>(&body).unwrap().len()
+        );
+
+        let (_, body) = respond(&trove, "GET", "/commands?namespace=kube");
+        let commands: Vec<HoardCmd> = serde_json::from_str(&body).unwrap();
+        assert_eq!(
+            vec!["pods"],
+            commands.iter().map(|c| &c.name).collect::<Vec<_>>()
+        );
+
+        let (_, body) = respond(&trove, "GET", "/search?q=command%7E%22git%20st%22");
+        let commands: Vec<HoardCmd> = serde_json::from_str(&body).unwrap();
+        assert_eq!("status", commands[0].name);
+
+        assert_eq!(
+            "400 Bad Request",
+            respond(&trove, "GET", "/search?q=foo:bar").0
+        );
+        assert_eq!("404 Not Found", respond(&trove, "GET", "/nope").0);
+        assert_eq!(
+            "405 Method Not Allowed",
+            respond(&trove, "POST", "/commands").0
+        );
+    }
+
+    #[test]
+    fn handle_request() {
+        let trove = RwLock::new(trove());
+        let mut stream =
+            io::Cursor::new(b"GET /commands HTTP/1.1\r\nHost: localhost:8080\r\n\r\n".to_vec());
+        handle_connection(&mut stream, &trove, &["localhost:8080".to_string()]).unwrap();
+        let response = String::from_utf8(stream.into_inner()).unwrap();
+        assert!(response.contains("HTTP/1.1 200 OK\r\n"));
+        assert!(response.ends_with(']'));
+    }
+
+    #[test]
+    fn rejects_bad_requests() {
+        let trove = RwLock::new(trove());
+        let hosts = ["127.0.0.1:8080".to_string()];
+        let status = |request: Vec<u8>| {
+            let mut stream = io::Cursor::new(request);
+            handle_connection(&mut stream, &trove, &hosts).unwrap();
+            // The response is written after the consumed part of the request
+            let response = String::from_utf8(stream.into_inner()).unwrap();
+            let status_line = &response[response.find("HTTP/1.1 ").unwrap()..];
+            status_line[..status_line.find("\r\n").unwrap()].to_string()
+        };
+
+        let rebound = b"GET /commands HTTP/1.1\r\nHost: evil.example:8080\r\n\r\n".to_vec();
+        assert_eq!("HTTP/1.1 403 Forbidden", status(rebound));
+        let no_host = b"GET /commands HTTP/1.1\r\n\r\n".to_vec();
+        assert_eq!("HTTP/1.1 403 Forbidden", status(no_host));
+
+        let mut endless = b"GET /".to_vec();
+        endless.resize(64 * 1024, b'a');
+        assert_eq!(
+            "HTTP/1.1 431 Request Header Fields Too Large",
+            status(endless)
+        );
+        let unterminated = b"GET /commands HTTP/1.1\r\nHost: 127.0.0.1:8080\r\n".to_vec();
+        assert_eq!(
+            "HTTP/1.1 431 Request Header Fields Too Large",
+            status(unterminated)
+        );
+    }
+
+    #[test]
+    fn watch_reloads_normalized_trove() {
+        let tmp_dir = tempfile::tempdir().unwrap();
+        let path = tmp_dir.path().join("trove.json");
+        let mut served = trove();
+        served.options.case_sensitive_namespaces = false;
+        let trove = Arc::new(RwLock::new(served));
+
+        let reloaded = Trove::from_commands(&[HoardCmd::default()
+            .with_name("log")
+            .with_namespace("git")
+            .with_command("git log")
+            .with_tags_raw("vcs,vcs")]);
+        let stop = Arc::new(AtomicBool::new(false));
+        let watcher = watch_trove_file(
+            Arc::clone(&trove),
+            path.clone(),
+            Duration::from_millis(10),
+            Arc::clone(&stop),
+        );
+        // A json trove with duplicate tags, whose namespace set is not stored
+        let mut json: serde_json::Value = serde_json::from_str(&reloaded.to_json()).unwrap();
+        json["commands"][0]["tags"] = serde_json::json!(["vcs", "vcs"]);
+        json["namespaces"] = serde_json::json!([]);
+        fs::write(&path, json.to_string()).unwrap();
+
+        let deadline = SystemTime::now() + Duration::from_secs(5);
+        while trove.read().unwrap().commands.len() != 1 && SystemTime::now() < deadline {
+            thread::sleep(Duration::from_millis(10));
+        }
+        stop.store(true, Ordering::Relaxed);
+        watcher.join().unwrap();
+        let trove = trove.read().unwrap().clone();
+        assert_eq!("log", trove.commands[0].name);
+        assert_eq!(vec!["vcs"], trove.commands[0].tags);
+        assert!(trove.has_namespace("git"));
+        assert!(!trove.options.case_sensitive_namespaces);
+    }
+
+    #[test]
+    fn refuses_non_loopback_address() {
+        let trove = Arc::new(RwLock::new(trove()));
+        assert!(serve(trove, "0.0.0.0:0").is_err());
+    }
+}